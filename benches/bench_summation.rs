@@ -0,0 +1,50 @@
+mod common;
+use std::hint::black_box;
+
+use criterion::{
+    criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, BenchmarkId, Criterion,
+    Throughput,
+};
+use xsum::{
+    summation::{KahanSum, NaiveSum, NeumaierSum, PairwiseSum, Summation},
+    XsumAuto, XsumLarge, XsumSmall,
+};
+
+use crate::common::DATA_MAP_F64;
+
+fn bench_strategy<S: Summation>(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    size: usize,
+    arr: &[f64],
+) {
+    group.bench_with_input(
+        BenchmarkId::new(format!("{} add_list", S::name()), size),
+        arr,
+        |bench, arr| {
+            bench.iter(|| {
+                let mut summation = S::new();
+                summation.add_list(black_box(arr));
+                black_box(summation.sum());
+            })
+        },
+    );
+}
+
+fn summation_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("summation");
+    for (size, array) in DATA_MAP_F64.iter() {
+        group.throughput(Throughput::Elements(*size as u64));
+
+        bench_strategy::<NaiveSum>(&mut group, *size, array);
+        bench_strategy::<PairwiseSum>(&mut group, *size, array);
+        bench_strategy::<KahanSum>(&mut group, *size, array);
+        bench_strategy::<NeumaierSum>(&mut group, *size, array);
+        bench_strategy::<XsumSmall>(&mut group, *size, array);
+        bench_strategy::<XsumLarge>(&mut group, *size, array);
+        bench_strategy::<XsumAuto>(&mut group, *size, array);
+    }
+    group.finish();
+}
+
+criterion_group!(benches, summation_bench);
+criterion_main!(benches);
@@ -11,14 +11,14 @@ const fn generate_array<const N: usize>() -> [f64; N] {
     arr
 }
 
-const ARRAY10: [f64; 10] = generate_array::<10>();
-const ARRAY100: [f64; 100] = generate_array::<100>();
-const ARRAY1000: [f64; 1_000] = generate_array::<1_000>();
-const ARRAY5000: [f64; 5_000] = generate_array::<5_000>();
-const ARRAY10000: [f64; 10_000] = generate_array::<10_000>();
-const ARRAY20000: [f64; 20_000] = generate_array::<20_000>();
-const ARRAY50000: [f64; 50_000] = generate_array::<50_000>();
-const ARRAY100000: [f64; 100_000] = generate_array::<100_000>();
+static ARRAY10: [f64; 10] = generate_array::<10>();
+static ARRAY100: [f64; 100] = generate_array::<100>();
+static ARRAY1000: [f64; 1_000] = generate_array::<1_000>();
+static ARRAY5000: [f64; 5_000] = generate_array::<5_000>();
+static ARRAY10000: [f64; 10_000] = generate_array::<10_000>();
+static ARRAY20000: [f64; 20_000] = generate_array::<20_000>();
+static ARRAY50000: [f64; 50_000] = generate_array::<50_000>();
+static ARRAY100000: [f64; 100_000] = generate_array::<100_000>();
 
 pub(crate) static DATA_MAP_F64: LazyLock<HashMap<usize, &'static [f64]>> = LazyLock::new(|| {
     HashMap::from([
@@ -1,2 +1,3 @@
+pub(crate) mod fixed_point;
 pub(crate) mod large_accumulator;
 pub(crate) mod small_accumulator;
@@ -0,0 +1,560 @@
+use std::{cmp::Ordering, fmt::Write};
+
+use crate::{
+    constants::{
+        XSUM_EXP_BIAS, XSUM_EXP_MASK, XSUM_LOW_MANTISSA_BITS, XSUM_MANTISSA_BITS,
+        XSUM_MANTISSA_MASK, XSUM_SCHUNKS,
+    },
+    parse_hex_error::ParseHexError,
+    rounding_mode::RoundingMode,
+    sign::Sign,
+};
+
+// Exponent of the lowest bit of the small accumulator, 2^-1075
+pub(crate) const XSUM_FIXED_POINT_EXP: i64 = -(XSUM_EXP_BIAS + XSUM_MANTISSA_BITS);
+
+// Decimal places that hold any fixed point value exactly, as 2^-1075 has that many
+pub(crate) const XSUM_EXACT_DECIMAL_PLACES: u32 = -XSUM_FIXED_POINT_EXP as u32;
+
+/// Sign and magnitude of the value of a small accumulator after carry propagation.
+///
+/// The magnitude is `digits` in base 2^32, lowest digit first, times 2^-1075, so that
+/// digit `k` lines up with chunk `k` of the small accumulator. There are no leading zero digits.
+#[cfg_attr(debug_assertions, derive(Debug))]
+pub(crate) struct FixedPoint {
+    pub(crate) negative: bool,
+    pub(crate) digits: Vec<u32>,
+}
+
+/// Result of rounding a `FixedPoint` to a given number of bits, with value `mantissa` * 2^`exp`
+#[cfg_attr(debug_assertions, derive(Debug))]
+pub(crate) struct Rounded {
+    pub(crate) negative: bool,
+    pub(crate) mantissa: u64,
+    pub(crate) exp: i64,
+}
+
+/// Value of a hexadecimal floating-point literal
+#[cfg_attr(debug_assertions, derive(Debug))]
+pub(crate) enum HexFloat {
+    /// A finite value, which is a signed zero if there are no digits
+    Finite(FixedPoint),
+    /// An infinity or a NaN
+    Special(f64),
+}
+
+impl HexFloat {
+    /// Parses a C99 hexadecimal floating-point literal such as `-0x1.8p+3`, where the exponent
+    /// may be left out, or `inf`, `infinity` or `nan` in any case, each with an optional sign.
+    ///
+    /// The value must be a multiple of 2^-1074 and fit in the chunks of a small accumulator.
+    pub(crate) fn parse(s: &str) -> Result<Self, ParseHexError> {
+        let (negative, rest) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        if rest.eq_ignore_ascii_case("inf") || rest.eq_ignore_ascii_case("infinity") {
+            let infinity: f64 = if negative {
+                f64::NEG_INFINITY
+            } else {
+                f64::INFINITY
+            };
+            return Ok(Self::Special(infinity));
+        }
+        if rest.eq_ignore_ascii_case("nan") {
+            return Ok(Self::Special(if negative { -f64::NAN } else { f64::NAN }));
+        }
+
+        let rest: &str = rest
+            .strip_prefix("0x")
+            .or_else(|| rest.strip_prefix("0X"))
+            .ok_or(ParseHexError::Invalid)?;
+        let (significand, exp): (&str, i64) = match rest.find(['p', 'P']) {
+            Some(i) => (&rest[..i], Self::parse_exponent(&rest[i + 1..])?),
+            None => (rest, 0),
+        };
+        let (int_part, frac_part) = significand.split_once('.').unwrap_or((significand, ""));
+        let hex_digits: Vec<u32> = int_part
+            .chars()
+            .chain(frac_part.chars())
+            .map(|c| c.to_digit(16).ok_or(ParseHexError::Invalid))
+            .collect::<Result<_, _>>()?;
+        if hex_digits.is_empty() {
+            return Err(ParseHexError::Invalid);
+        }
+
+        // Eight hex digits make one digit in base 2^32, starting from the last one.
+        let magnitude = FixedPoint {
+            negative,
+            digits: hex_digits
+                .rchunks(8)
+                .map(|chunk| chunk.iter().fold(0, |acc, &d| (acc << 4) | d))
+                .collect(),
+        };
+        // The literal is the integer `magnitude` times 2^(exp - 4 * frac_part.len()).
+        FixedPoint::from_scaled_integer(magnitude, exp - 4 * frac_part.len() as i64)
+            .map(Self::Finite)
+    }
+
+    /// Parses a signed decimal exponent. Exponents beyond what any literal can make up for
+    /// are clamped, so that they stay out of range without overflowing.
+    fn parse_exponent(s: &str) -> Result<i64, ParseHexError> {
+        let (negative, digits) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseHexError::Invalid);
+        }
+        let magnitude: i64 = digits
+            .bytes()
+            .fold(0, |acc, b| (acc * 10 + i64::from(b - b'0')).min(1 << 48));
+        Ok(if negative { -magnitude } else { magnitude })
+    }
+}
+
+impl Rounded {
+    /// Returns the value as an `f64`, which must be able to hold it exactly.
+    pub(crate) fn to_f64(&self) -> f64 {
+        // 2^exp, which is a normal or subnormal f64
+        let scale: f64 = if self.exp >= 1 - XSUM_EXP_BIAS {
+            f64::from_bits(((self.exp + XSUM_EXP_BIAS) << XSUM_MANTISSA_BITS) as u64)
+        } else {
+            f64::from_bits(1 << (self.exp - XSUM_FIXED_POINT_EXP - 1))
+        };
+        // Both factors are exact, and so is their product.
+        let magnitude: f64 = self.mantissa as f64 * scale;
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// Returns the exponent of the highest bit of the mantissa, which must not be zero.
+    pub(crate) const fn top_exp(&self) -> i64 {
+        self.exp + 63 - self.mantissa.leading_zeros() as i64
+    }
+
+    /// Returns the value with a magnitude one unit in the last place larger, keeping
+    /// at most `precision` significant bits.
+    pub(crate) const fn next_away(&self, precision: i64) -> Self {
+        let mut mantissa: u64 = self.mantissa + 1;
+        let mut exp: i64 = self.exp;
+        if mantissa >> precision != 0 {
+            mantissa >>= 1;
+            exp += 1;
+        }
+        Self {
+            negative: self.negative,
+            mantissa,
+            exp,
+        }
+    }
+}
+
+impl FixedPoint {
+    /// Converts chunks holding any signed values into sign and magnitude.
+    pub(crate) fn from_chunks(chunks: &[i64]) -> Self {
+        let mut digits: Vec<u32> = Vec::with_capacity(chunks.len() + 2);
+        let mut carry: i128 = 0;
+        for &chunk in chunks {
+            let c: i128 = chunk as i128 + carry;
+            digits.push(c as u32);
+            carry = c >> XSUM_LOW_MANTISSA_BITS;
+        }
+        while carry != 0 && carry != -1 {
+            digits.push(carry as u32);
+            carry >>= XSUM_LOW_MANTISSA_BITS;
+        }
+
+        // A final carry of -1 means the digits are the two's complement of the magnitude.
+        let negative: bool = carry == -1;
+        if negative {
+            let mut borrow: bool = true;
+            for digit in &mut digits {
+                let (d, overflow) = (!*digit).overflowing_add(u32::from(borrow));
+                *digit = d;
+                borrow = overflow;
+            }
+            if borrow {
+                digits.push(1);
+            }
+        }
+
+        let mut fixed_point = Self { negative, digits };
+        fixed_point.trim();
+        fixed_point
+    }
+
+    /// Returns `mantissa` * 2^`exp`, where `exp` is at least -1075.
+    pub(crate) fn from_mantissa(negative: bool, mantissa: u64, exp: i64) -> Self {
+        let fixed_point = Self {
+            negative,
+            digits: vec![mantissa as u32, (mantissa >> XSUM_LOW_MANTISSA_BITS) as u32],
+        };
+        fixed_point.shifted(exp - XSUM_FIXED_POINT_EXP)
+    }
+
+    /// Returns the exact value of the finite `value`.
+    pub(crate) fn from_f64(value: f64) -> Self {
+        let bits: i64 = value.to_bits() as i64;
+        let exp: i64 = (bits >> XSUM_MANTISSA_BITS) & XSUM_EXP_MASK;
+        let mut mantissa: u64 = (bits & XSUM_MANTISSA_MASK) as u64;
+        if exp != 0 {
+            mantissa |= 1 << XSUM_MANTISSA_BITS;
+        }
+        // subnormals have the exponent of the smallest normal number
+        Self::from_mantissa(bits < 0, mantissa, exp.max(1) + XSUM_FIXED_POINT_EXP)
+    }
+
+    /// Returns the integer `magnitude` times 2^`exp`, which must be a multiple of 2^-1074
+    /// and fit in the chunks of a small accumulator. A zero keeps its sign.
+    pub(crate) fn from_scaled_integer(
+        mut magnitude: Self,
+        exp: i64,
+    ) -> Result<Self, ParseHexError> {
+        magnitude.trim();
+        if magnitude.is_zero() {
+            return Ok(magnitude);
+        }
+
+        // Bit 1 of a fixed point value is 2^-1074.
+        let shift: i64 = exp.saturating_sub(XSUM_FIXED_POINT_EXP);
+        if magnitude.lowest_set_bit().saturating_add(shift) < 1 {
+            return Err(ParseHexError::Inexact);
+        }
+        if magnitude.bit_len().saturating_add(shift)
+            > i64::from(XSUM_SCHUNKS) * XSUM_LOW_MANTISSA_BITS
+        {
+            return Err(ParseHexError::OutOfRange);
+        }
+        Ok(magnitude.shifted(shift))
+    }
+
+    /// Returns the integer in 64-bit `limbs`, lowest limb first, times 2^`exp`, with `sign`,
+    /// if it can be added to a small accumulator exactly, as for `from_scaled_integer`.
+    pub(crate) fn from_limbs(sign: Sign, limbs: &[u64], exp: i64) -> Option<Self> {
+        let magnitude = Self {
+            negative: sign == Sign::Negative,
+            digits: limbs
+                .iter()
+                .flat_map(|&limb| [limb as u32, (limb >> XSUM_LOW_MANTISSA_BITS) as u32])
+                .collect(),
+        };
+        Self::from_scaled_integer(magnitude, exp).ok()
+    }
+
+    /// Returns the value times 2^`shift`. Bits shifted below 2^-1075 are lost.
+    pub(crate) fn shifted(&self, shift: i64) -> Self {
+        let word_shift: usize = (shift.unsigned_abs() / XSUM_LOW_MANTISSA_BITS as u64) as usize;
+        let bit_shift: u32 = (shift.unsigned_abs() % XSUM_LOW_MANTISSA_BITS as u64) as u32;
+        let mut digits: Vec<u32>;
+
+        if shift >= 0 {
+            digits = vec![0; word_shift];
+            let mut carry: u32 = 0;
+            for &digit in &self.digits {
+                let wide: u64 = (u64::from(digit) << bit_shift) | u64::from(carry);
+                digits.push(wide as u32);
+                carry = (wide >> XSUM_LOW_MANTISSA_BITS) as u32;
+            }
+            digits.push(carry);
+        } else {
+            digits = Vec::with_capacity(self.digits.len());
+            for k in word_shift..self.digits.len() {
+                let high: u64 = self.digits.get(k + 1).map_or(0, |&digit| u64::from(digit));
+                let wide: u64 = (high << XSUM_LOW_MANTISSA_BITS) | u64::from(self.digits[k]);
+                digits.push((wide >> bit_shift) as u32);
+            }
+        }
+
+        let mut fixed_point = Self {
+            negative: self.negative,
+            digits,
+        };
+        fixed_point.trim();
+        fixed_point
+    }
+
+    /// Multiplies the magnitude by `n`.
+    pub(crate) fn mul_u64(&mut self, n: u64) {
+        let mut carry: u128 = 0;
+        for digit in &mut self.digits {
+            let wide: u128 = u128::from(*digit) * u128::from(n) + carry;
+            *digit = wide as u32;
+            carry = wide >> XSUM_LOW_MANTISSA_BITS;
+        }
+        while carry != 0 {
+            self.digits.push(carry as u32);
+            carry >>= XSUM_LOW_MANTISSA_BITS;
+        }
+        self.trim();
+    }
+
+    /// Removes leading zero digits.
+    pub(crate) fn trim(&mut self) {
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Compares the values, with `+0` and `-0` equal.
+    pub(crate) fn cmp_value(&self, other: &Self) -> Ordering {
+        let sign = |fixed_point: &Self| -> i8 {
+            if fixed_point.is_zero() {
+                0
+            } else if fixed_point.negative {
+                -1
+            } else {
+                1
+            }
+        };
+        match sign(self).cmp(&sign(other)) {
+            Ordering::Equal => {}
+            ordering => return ordering,
+        }
+
+        // Without leading zero digits, the longer magnitude is the larger one.
+        let magnitude: Ordering = self
+            .digits
+            .len()
+            .cmp(&other.digits.len())
+            .then_with(|| self.digits.iter().rev().cmp(other.digits.iter().rev()));
+        if self.negative {
+            magnitude.reverse()
+        } else {
+            magnitude
+        }
+    }
+
+    /// Returns the number of bits of the magnitude, or 0 if it is zero.
+    pub(crate) fn bit_len(&self) -> i64 {
+        match self.digits.last() {
+            Some(&top) => {
+                self.digits.len() as i64 * XSUM_LOW_MANTISSA_BITS - i64::from(top.leading_zeros())
+            }
+            None => 0,
+        }
+    }
+
+    /// Returns the index of the lowest 1 bit of the magnitude, which must not be zero.
+    pub(crate) fn lowest_set_bit(&self) -> i64 {
+        let k: usize = self.digits.iter().take_while(|&&digit| digit == 0).count();
+        k as i64 * XSUM_LOW_MANTISSA_BITS + i64::from(self.digits[k].trailing_zeros())
+    }
+
+    /// Formats the magnitude, which must not be zero, with its sign in C99 `%a` style, such
+    /// as `-0x1.8p+3`, with as many hex digits as it needs.
+    pub(crate) fn to_hex(&self) -> String {
+        let top: i64 = self.bit_len() - 1;
+        let nibbles: i64 = (top - self.lowest_set_bit() + 3) / 4;
+
+        let mut hex = String::from(if self.negative { "-0x1" } else { "0x1" });
+        if nibbles > 0 {
+            hex.push('.');
+        }
+        for j in 1..=nibbles {
+            let nibble: u32 = (0..4).fold(0, |acc, b| {
+                acc | (u32::from(self.bit(top - 4 * j + b)) << b)
+            });
+            hex.push(char::from_digit(nibble, 16).unwrap_or('0'));
+        }
+        let _ = write!(hex, "p{:+}", top + XSUM_FIXED_POINT_EXP);
+        hex
+    }
+
+    /// Returns bit `i` of the magnitude, which is 0 outside of the digits.
+    pub(crate) fn bit(&self, i: i64) -> bool {
+        if i < 0 {
+            return false;
+        }
+        let k: usize = (i / XSUM_LOW_MANTISSA_BITS) as usize;
+        match self.digits.get(k) {
+            Some(&digit) => (digit >> (i % XSUM_LOW_MANTISSA_BITS)) & 1 != 0,
+            None => false,
+        }
+    }
+
+    /// Returns `true` if any bit of the magnitude below bit `i` is 1.
+    pub(crate) fn any_bits_below(&self, i: i64) -> bool {
+        if i <= 0 {
+            return false;
+        }
+        let k: usize = (i / XSUM_LOW_MANTISSA_BITS) as usize;
+        let partial: u32 = (i % XSUM_LOW_MANTISSA_BITS) as u32;
+        if self.digits.iter().take(k).any(|&digit| digit != 0) {
+            return true;
+        }
+        partial != 0
+            && self
+                .digits
+                .get(k)
+                .is_some_and(|&digit| digit & ((1u32 << partial) - 1) != 0)
+    }
+
+    /// Returns bits `lo` to `lo + 63` of the magnitude; bits below 0 are 0.
+    pub(crate) fn bits_from(&self, lo: i64) -> u64 {
+        let mut bits: u64 = 0;
+        for n in 0..64 {
+            if self.bit(lo + n) {
+                bits |= 1 << n;
+            }
+        }
+        bits
+    }
+
+    /// Rounds the value to an integer with `mode`, and returns the magnitude of that integer
+    /// in base 2^32, lowest digit first, with no leading zero digits.
+    pub(crate) fn round_to_integer(&self, mode: RoundingMode) -> Vec<u32> {
+        // Bit 1075 of the magnitude is 2^0.
+        self.round_to_bit(-XSUM_FIXED_POINT_EXP, mode)
+    }
+
+    /// Rounds the value with `mode` to a multiple of bit `units` of the magnitude, and returns
+    /// the magnitude in those units, in base 2^32, lowest digit first, with no leading zero digits.
+    pub(crate) fn round_to_bit(&self, units: i64, mode: RoundingMode) -> Vec<u32> {
+        let mut integer: Vec<u32> = self.shifted(-units).digits;
+        let odd: bool = integer.first().is_some_and(|&digit| digit & 1 != 0);
+
+        let half: bool = self.bit(units - 1);
+        let below_half: bool = self.any_bits_below(units - 1);
+        let round_away: bool = match mode {
+            RoundingMode::HalfEven => half && (below_half || odd),
+            RoundingMode::HalfUp => half,
+            RoundingMode::TowardZero => false,
+            RoundingMode::Floor => self.negative && (half || below_half),
+            RoundingMode::Ceil => !self.negative && (half || below_half),
+        };
+        if round_away {
+            let mut carry: bool = true;
+            for digit in &mut integer {
+                (*digit, carry) = digit.overflowing_add(1);
+                if !carry {
+                    break;
+                }
+            }
+            if carry {
+                integer.push(1);
+            }
+        }
+        integer
+    }
+
+    /// Returns an integer from `round_to_integer` as `u128`, if it fits.
+    pub(crate) fn integer_to_u128(integer: &[u32]) -> Option<u128> {
+        if integer.len() > 4 {
+            return None;
+        }
+        Some(integer.iter().rev().fold(0, |acc, &digit| {
+            (acc << XSUM_LOW_MANTISSA_BITS) | u128::from(digit)
+        }))
+    }
+
+    /// Returns the decimal digits of an integer from `round_to_integer`.
+    pub(crate) fn integer_to_decimal(integer: &[u32]) -> String {
+        // Dividing by 10^9 gives nine decimal digits at a time, lowest first.
+        const BILLION: u64 = 1_000_000_000;
+        let mut quotient: Vec<u32> = integer.to_vec();
+        let mut groups: Vec<u32> = Vec::new();
+        while !quotient.is_empty() {
+            let mut remainder: u64 = 0;
+            for digit in quotient.iter_mut().rev() {
+                let wide: u64 = (remainder << XSUM_LOW_MANTISSA_BITS) | u64::from(*digit);
+                *digit = (wide / BILLION) as u32;
+                remainder = wide % BILLION;
+            }
+            groups.push(remainder as u32);
+            while quotient.last() == Some(&0) {
+                quotient.pop();
+            }
+        }
+
+        let mut decimal: String = groups.pop().unwrap_or(0).to_string();
+        for group in groups.iter().rev() {
+            let _ = write!(decimal, "{group:09}");
+        }
+        decimal
+    }
+
+    /// Returns the value times 10^`places`.
+    pub(crate) fn scaled_by_pow10(&self, places: u32) -> Self {
+        // 10^19 is the largest power of ten in u64.
+        let mut scaled = Self {
+            negative: self.negative,
+            digits: self.digits.clone(),
+        };
+        for _ in 0..places / 19 {
+            scaled.mul_u64(10_u64.pow(19));
+        }
+        scaled.mul_u64(10_u64.pow(places % 19));
+        scaled
+    }
+
+    /// Returns the fractional part of the value, with the sign of the value.
+    pub(crate) fn fract(&self) -> Self {
+        // Bit 1075 of the magnitude is 2^0.
+        let units: usize = (-XSUM_FIXED_POINT_EXP) as usize;
+        let whole: usize = units / XSUM_LOW_MANTISSA_BITS as usize;
+        let partial: u32 = (units % XSUM_LOW_MANTISSA_BITS as usize) as u32;
+
+        let mut digits: Vec<u32> = self.digits.iter().take(whole + 1).copied().collect();
+        if let Some(top) = digits.get_mut(whole) {
+            *top &= (1 << partial) - 1;
+        }
+        let mut fraction = Self {
+            negative: self.negative,
+            digits,
+        };
+        fraction.trim();
+        fraction
+    }
+
+    /// Truncates the magnitude toward zero, keeping at most `precision` (at most 63)
+    /// significant bits, and no bits below 2^`min_exp`. Also returns whether any bits were lost.
+    pub(crate) fn truncate(&self, precision: i64, min_exp: i64) -> (Rounded, bool) {
+        // Index of the lowest kept bit
+        let top: i64 = self.bit_len() - 1;
+        let lsb: i64 = (top - (precision - 1)).max(min_exp - XSUM_FIXED_POINT_EXP);
+
+        let truncated = Rounded {
+            negative: self.negative,
+            mantissa: self.bits_from(lsb),
+            exp: lsb + XSUM_FIXED_POINT_EXP,
+        };
+        (truncated, self.any_bits_below(lsb))
+    }
+
+    /// Rounds the magnitude to nearest, ties to even, keeping at most `precision` (at most 63)
+    /// significant bits, and no bits below 2^`min_exp`.
+    pub(crate) fn round(&self, precision: i64, min_exp: i64) -> Rounded {
+        // Index of the lowest kept bit
+        let top: i64 = self.bit_len() - 1;
+        let lsb: i64 = (top - (precision - 1)).max(min_exp - XSUM_FIXED_POINT_EXP);
+
+        let mut mantissa: u64 = self.bits_from(lsb);
+        let round_bit: bool = self.bit(lsb - 1);
+        let sticky: bool = self.any_bits_below(lsb - 1);
+        let mut exp: i64 = lsb + XSUM_FIXED_POINT_EXP;
+
+        if round_bit && (sticky || mantissa & 1 != 0) {
+            mantissa += 1;
+            if mantissa >> precision != 0 {
+                mantissa >>= 1;
+                exp += 1;
+            }
+        }
+
+        Rounded {
+            negative: self.negative,
+            mantissa,
+            exp,
+        }
+    }
+}
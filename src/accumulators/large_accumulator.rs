@@ -1,9 +1,13 @@
+use std::fmt::Write;
+
 use crate::{
     accumulators::small_accumulator::SmallAccumulator,
     constants::{
-        XSUM_EXP_BITS, XSUM_EXP_MASK, XSUM_LCHUNKS, XSUM_LCOUNT_BITS, XSUM_LOW_EXP_BITS,
-        XSUM_LOW_EXP_MASK, XSUM_LOW_MANTISSA_BITS, XSUM_LOW_MANTISSA_MASK, XSUM_MANTISSA_BITS,
+        XSUM_EXP_BIAS, XSUM_EXP_BITS, XSUM_EXP_MASK, XSUM_LCHUNKS, XSUM_LCOUNT_BITS,
+        XSUM_LOW_EXP_BITS, XSUM_LOW_EXP_MASK, XSUM_LOW_MANTISSA_BITS, XSUM_LOW_MANTISSA_MASK,
+        XSUM_MANTISSA_BITS,
     },
+    policy::SpecialValuePolicy,
 };
 
 #[cfg_attr(debug_assertions, derive(Debug))]
@@ -17,13 +21,17 @@ pub(crate) struct LargeAccumulator {
 
 impl LargeAccumulator {
     pub(crate) fn new() -> Self {
+        Self::with_policy(SpecialValuePolicy::default())
+    }
+
+    pub(crate) fn with_policy(policy: SpecialValuePolicy) -> Self {
         //  : m_chunk(XSUM_LCHUNKS), m_count(XSUM_LCHUNKS, -1), m_chunksUsed(XSUM_LCHUNKS / 64, 0), m_usedUsed{0}, m_sacc{}
         Self {
             m_chunk: vec![0; XSUM_LCHUNKS],
             m_count: vec![-1; XSUM_LCHUNKS],
             m_chunks_used: vec![0; XSUM_LCHUNKS / 64],
             m_used_used: 0,
-            m_sacc: SmallAccumulator::new(),
+            m_sacc: SmallAccumulator::with_policy(policy),
         }
     }
 
@@ -33,68 +41,7 @@ impl LargeAccumulator {
         // Add to the small accumulator only if the count is not -1, which
         // indicates a chunk that contains nothing yet.
         if count >= 0 {
-            // Propagate carries in the small accumulator if necessary.
-            if self.m_sacc.m_adds_until_propagate == 0 {
-                self.m_sacc.carry_propagate();
-            }
-
-            // Get the chunk we will add.  Note that this chunk is the integer sum
-            // of entire 64-bit floating-point representations, with sign, exponent,
-            // and mantissa, but we want only the sum of the mantissas.
-            let mut chunk = self.m_chunk[ix];
-
-            // If we added the maximum number of values to 'chunk', the sum of
-            // the sign and exponent parts (all the same, equal to the index) will
-            // have overflowed out the top, leaving only the sum of the mantissas.
-            // If the count of how many more terms we could have summed is greater
-            // than zero, we therefore add this count times the index (shifted to
-            // the position of the sign and exponent) to get the unwanted bits to
-            // overflow out the top.
-            if count > 0 {
-                chunk = chunk.wrapping_add((count as u64 * ix as u64) << XSUM_MANTISSA_BITS);
-            }
-
-            // Find the exponent for this chunk from the low bits of the index,
-            // and split it into low and high parts, for accessing the small
-            // accumulator.  Noting that for denormalized numbers where the
-            // exponent part is zero, the actual exponent is 1 (before subtracting
-            // the bias), not zero.
-            let exp: i32 = ix as i32 & XSUM_EXP_MASK as i32;
-            let mut low_exp: i32 = exp & XSUM_LOW_EXP_MASK as i32;
-            let mut high_exp: usize = (exp >> XSUM_LOW_EXP_BITS) as usize;
-            if exp == 0 {
-                low_exp = 1;
-                high_exp = 0;
-            }
-
-            // Split the mantissa into three parts, for three consecutive chunks in
-            // the small accumulator.  Except for denormalized numbers, add in the sum
-            // of all the implicit 1 bits that are above the actual mantissa bits.
-            let low_chunk: i64 = (chunk << low_exp) as i64 & XSUM_LOW_MANTISSA_MASK;
-            let mut mid_chunk: i64 = chunk as i64 >> (XSUM_LOW_MANTISSA_BITS - low_exp as i64);
-            if exp != 0 {
-                // normalized
-                mid_chunk += ((1 << XSUM_LCOUNT_BITS) as i64 - count as i64)
-                    << (XSUM_MANTISSA_BITS - XSUM_LOW_MANTISSA_BITS + low_exp as i64);
-            }
-            let high_chunk: i64 = mid_chunk >> XSUM_LOW_MANTISSA_BITS;
-            mid_chunk &= XSUM_LOW_MANTISSA_MASK;
-
-            // Add or subtract the three parts of the mantissa from three small
-            // accumulator chunks, according to the sign that is part of the index.
-            if ix & (1 << XSUM_EXP_BITS) != 0 {
-                self.m_sacc.m_chunk[high_exp] -= low_chunk;
-                self.m_sacc.m_chunk[high_exp + 1] -= mid_chunk;
-                self.m_sacc.m_chunk[high_exp + 2] -= high_chunk;
-            } else {
-                self.m_sacc.m_chunk[high_exp] += low_chunk;
-                self.m_sacc.m_chunk[high_exp + 1] += mid_chunk;
-                self.m_sacc.m_chunk[high_exp + 2] += high_chunk;
-            }
-
-            // The above additions/subtractions reduce by one the number we can
-            // do before we need to do carry propagation again.
-            self.m_sacc.m_adds_until_propagate -= 1;
+            Self::add_chunk_to(&mut self.m_sacc, ix, self.m_chunk[ix], count);
         }
 
         // We now clear the chunk to zero, and set the count to the number
@@ -107,10 +54,127 @@ impl LargeAccumulator {
         self.m_used_used |= 1u64 << (ix >> 6);
     }
 
+    /// Adds large chunk `ix`, holding `chunk` with `count` adds left, to `sacc`.
+    fn add_chunk_to(sacc: &mut SmallAccumulator, ix: usize, chunk: u64, count: i32) {
+        // Propagate carries in the small accumulator if necessary.
+        if sacc.m_adds_until_propagate == 0 {
+            sacc.carry_propagate();
+        }
+
+        // Get the chunk we will add.  Note that this chunk is the integer sum
+        // of entire 64-bit floating-point representations, with sign, exponent,
+        // and mantissa, but we want only the sum of the mantissas.
+        let mut chunk = chunk;
+
+        // If we added the maximum number of values to 'chunk', the sum of
+        // the sign and exponent parts (all the same, equal to the index) will
+        // have overflowed out the top, leaving only the sum of the mantissas.
+        // If the count of how many more terms we could have summed is greater
+        // than zero, we therefore add this count times the index (shifted to
+        // the position of the sign and exponent) to get the unwanted bits to
+        // overflow out the top.
+        if count > 0 {
+            chunk = chunk.wrapping_add((count as u64 * ix as u64) << XSUM_MANTISSA_BITS);
+        }
+
+        // Find the exponent for this chunk from the low bits of the index,
+        // and split it into low and high parts, for accessing the small
+        // accumulator.  Noting that for denormalized numbers where the
+        // exponent part is zero, the actual exponent is 1 (before subtracting
+        // the bias), not zero.
+        let exp: i32 = ix as i32 & XSUM_EXP_MASK as i32;
+        let mut low_exp: i32 = exp & XSUM_LOW_EXP_MASK as i32;
+        let mut high_exp: usize = (exp >> XSUM_LOW_EXP_BITS) as usize;
+        if exp == 0 {
+            low_exp = 1;
+            high_exp = 0;
+        }
+
+        // Split the mantissa into three parts, for three consecutive chunks in
+        // the small accumulator.  Except for denormalized numbers, add in the sum
+        // of all the implicit 1 bits that are above the actual mantissa bits.
+        // The sum of up to 4096 mantissas, with their implicit 1 bits, may use all
+        // 64 bits, so the split must be done on unsigned values.
+        let low_chunk: i64 = ((chunk << low_exp) & XSUM_LOW_MANTISSA_MASK as u64) as i64;
+        let mut mid_chunk: u64 = chunk >> (XSUM_LOW_MANTISSA_BITS - low_exp as i64);
+        if exp != 0 {
+            // normalized
+            mid_chunk += ((1 << XSUM_LCOUNT_BITS) - count as u64)
+                << (XSUM_MANTISSA_BITS - XSUM_LOW_MANTISSA_BITS + low_exp as i64);
+        }
+        let high_chunk: i64 = (mid_chunk >> XSUM_LOW_MANTISSA_BITS) as i64;
+        let mid_chunk: i64 = (mid_chunk & XSUM_LOW_MANTISSA_MASK as u64) as i64;
+
+        // Add or subtract the three parts of the mantissa from three small
+        // accumulator chunks, according to the sign that is part of the index.
+        if ix & (1 << XSUM_EXP_BITS) != 0 {
+            sacc.m_chunk[high_exp] -= low_chunk;
+            sacc.m_chunk[high_exp + 1] -= mid_chunk;
+            sacc.m_chunk[high_exp + 2] -= high_chunk;
+        } else {
+            sacc.m_chunk[high_exp] += low_chunk;
+            sacc.m_chunk[high_exp + 1] += mid_chunk;
+            sacc.m_chunk[high_exp + 2] += high_chunk;
+        }
+
+        // The above additions/subtractions reduce by one the number we can
+        // do before we need to do carry propagation again.
+        sacc.m_adds_until_propagate -= 1;
+    }
+
+    /// Returns `true` if values were added to large chunk `ix` since it was last transferred.
+    fn has_pending(&self, ix: usize) -> bool {
+        let count: i32 = self.m_count[ix];
+        count >= 0 && count != 1 << XSUM_LCOUNT_BITS
+    }
+
+    /// Returns a copy of the small accumulator with every large chunk added in,
+    /// leaving this accumulator as it is. Only the chunks in use are looked at.
+    pub(crate) fn to_small(&self) -> SmallAccumulator {
+        let mut sacc = SmallAccumulator::new_based_on(&self.m_sacc);
+        for (p, &used) in self.m_chunks_used.iter().enumerate() {
+            let mut u: u64 = used;
+            while u != 0 {
+                let ix: usize = (p << 6) | u.trailing_zeros() as usize;
+                u &= u - 1;
+                if self.has_pending(ix) {
+                    Self::add_chunk_to(&mut sacc, ix, self.m_chunk[ix], self.m_count[ix]);
+                }
+            }
+        }
+        sacc
+    }
+
+    /// Appends the large chunks that received values since they were last
+    /// transferred, followed by the dump of the small accumulator, to `out`.
+    pub(crate) fn write_dump(&self, out: &mut String) {
+        let _ = writeln!(out, "large accumulator:");
+        for (ix, (&chunk, &count)) in self.m_chunk.iter().zip(&self.m_count).enumerate() {
+            // -1 is never used (or Inf/NaN), a full count means nothing was added yet
+            if count < 0 || count == 1 << XSUM_LCOUNT_BITS {
+                continue;
+            }
+            let exp: i64 = ix as i64 & XSUM_EXP_MASK;
+            let sign: char = if ix & (1 << XSUM_EXP_BITS) != 0 {
+                '-'
+            } else {
+                '+'
+            };
+            let low: i64 = exp.max(1) - XSUM_EXP_BIAS - XSUM_MANTISSA_BITS;
+            let high: i64 = exp - XSUM_EXP_BIAS;
+            let _ = writeln!(
+                out,
+                "  chunk[{ix:4}] = {chunk:#018x} ({sign}, {} adds, exponents {low}..{high})",
+                (1 << XSUM_LCOUNT_BITS) - count
+            );
+        }
+        self.m_sacc.write_dump(out);
+    }
+
     #[cold]
     pub(crate) fn large_add_value_inf_nan(&mut self, ix: usize, uintv: u64) {
         if (ix as i64 & XSUM_EXP_MASK) == XSUM_EXP_MASK {
-            self.m_sacc.add_inf_nan(uintv as i64);
+            self.m_sacc.add_special_value(uintv as i64);
         } else {
             self.add_lchunk_to_small(ix);
             self.m_count[ix] -= 1;
@@ -195,7 +259,8 @@ impl LargeAccumulator {
             }
 
             loop {
-                if self.m_count[ix] >= 0 {
+                // A chunk with nothing added since the last transfer adds nothing.
+                if self.has_pending(ix) {
                     self.add_lchunk_to_small(ix);
                 }
                 ix += 1;
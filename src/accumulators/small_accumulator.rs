@@ -1,28 +1,48 @@
-use crate::constants::{
-    XSUM_EXP_MASK, XSUM_LOW_EXP_BITS, XSUM_LOW_EXP_MASK, XSUM_LOW_MANTISSA_BITS,
-    XSUM_LOW_MANTISSA_MASK, XSUM_MANTISSA_BITS, XSUM_MANTISSA_MASK, XSUM_SCHUNKS, XSUM_SIGN_MASK,
-    XSUM_SMALL_CARRY_TERMS,
+use std::{
+    cmp::Ordering,
+    fmt::Write,
+    hash::{Hash, Hasher},
 };
 
+use crate::{
+    accumulators::fixed_point::{FixedPoint, XSUM_FIXED_POINT_EXP},
+    constants::{
+        XSUM_EXP_BIAS, XSUM_EXP_MASK, XSUM_LOW_EXP_BITS, XSUM_LOW_EXP_MASK, XSUM_LOW_MANTISSA_BITS,
+        XSUM_LOW_MANTISSA_MASK, XSUM_MANTISSA_BITS, XSUM_MANTISSA_MASK, XSUM_SCHUNKS,
+        XSUM_SIGN_MASK, XSUM_SMALL_CARRY_TERMS,
+    },
+    policy::SpecialValuePolicy,
+    rounding_mode::RoundingMode,
+};
+
+#[derive(Clone)]
 #[cfg_attr(debug_assertions, derive(Debug))]
 pub(crate) struct SmallAccumulator {
     pub(crate) m_chunk: Vec<i64>, // Chunks making up small accumulator
     pub(crate) m_adds_until_propagate: i64, // Number of remaining adds before carry
     pub(crate) m_inf: i64,        // If non-zero, +Inf, -Inf, or NaN
     pub(crate) m_nan: i64,        // If non-zero, a NaN value with payload
+    pub(crate) m_overflow: i64,   // If non-zero, the infinity the chunks overflowed toward
     pub(crate) m_size_count: usize, // number of added values
-    pub(crate) m_has_pos_number: bool, // check if added values have at least one positive number
+    pub(crate) m_has_pos_number: bool, // check if added values have at least one positive finite number
+    pub(crate) m_policy: SpecialValuePolicy, // how NaN and Inf inputs are handled
 }
 
 impl SmallAccumulator {
     pub(crate) fn new() -> Self {
+        Self::with_policy(SpecialValuePolicy::default())
+    }
+
+    pub(crate) fn with_policy(policy: SpecialValuePolicy) -> Self {
         Self {
             m_chunk: vec![0; XSUM_SCHUNKS as usize],
             m_adds_until_propagate: XSUM_SMALL_CARRY_TERMS,
             m_inf: 0,
             m_nan: 0,
+            m_overflow: 0,
             m_size_count: 0,
             m_has_pos_number: false,
+            m_policy: policy,
         }
     }
 
@@ -32,8 +52,10 @@ impl SmallAccumulator {
             m_adds_until_propagate: small_accumulator.m_adds_until_propagate,
             m_inf: small_accumulator.m_inf,
             m_nan: small_accumulator.m_nan,
+            m_overflow: small_accumulator.m_overflow,
             m_size_count: small_accumulator.m_size_count,
             m_has_pos_number: small_accumulator.m_has_pos_number,
+            m_policy: small_accumulator.m_policy,
         }
     }
 
@@ -83,6 +105,9 @@ impl SmallAccumulator {
             if chigh == 0 {
                 uix = i;
                 i += 1;
+                if i > u {
+                    break; // chunk u may be the top chunk, with nothing above it
+                }
                 continue; // no need to change this chunk
             }
 
@@ -104,12 +129,12 @@ impl SmallAccumulator {
             // the number of chunks is big enough to hold any sum, and we do not
             // store redundant chunks with values 0 or -1 above previously non-zero
             // chunks.  But other add operations might cause overflow, in which
-            // case we produce a NaN with all 1s as payload.  (We can't reliably produce
-            // an Inf of the right sign.)
+            // case we record it with the sign of the carry, which is the sign of
+            // the whole value since the chunks below cannot outweigh it.
 
             self.m_chunk[i as usize] = clow;
             if i + 1 >= XSUM_SCHUNKS {
-                self.add_inf_nan((XSUM_EXP_MASK << XSUM_MANTISSA_BITS) | XSUM_MANTISSA_MASK);
+                self.set_overflow(chigh < 0);
                 u = i;
             } else {
                 self.m_chunk[(i + 1) as usize] += chigh; // note: this could make this chunk be zero
@@ -147,25 +172,111 @@ impl SmallAccumulator {
 
     #[cold]
     pub(crate) fn add_inf_nan(&mut self, ivalue: i64) {
-        let mantissa: i64 = ivalue & XSUM_MANTISSA_MASK;
+        if ivalue & XSUM_MANTISSA_MASK == 0 {
+            self.m_inf = Self::merge_inf(self.m_inf, ivalue);
+        } else {
+            self.m_nan = Self::merge_nan(self.m_nan, ivalue);
+        }
+    }
 
-        if mantissa == 0 {
-            // Inf
-            if self.m_inf == 0 {
-                // no previous Inf
-                self.m_inf = ivalue;
-            } else if self.m_inf != ivalue {
-                // previous Inf was opposite sign
-                let mut fltv: f64 = f64::from_bits(ivalue as u64);
-                fltv -= fltv; // result will be a NaN
-                self.m_inf = fltv.to_bits() as i64;
-            }
+    /// Records that the finite value no longer fits in the chunks, with the sign it had.
+    /// The first overflow is kept, as the chunks hold no meaningful value after it.
+    pub(crate) fn set_overflow(&mut self, negative: bool) {
+        if self.m_overflow == 0 {
+            let sign: i64 = if negative { XSUM_SIGN_MASK } else { 0 };
+            self.m_overflow = sign | (XSUM_EXP_MASK << XSUM_MANTISSA_BITS);
+        }
+    }
+
+    /// Returns what `m_inf` becomes when the infinity `ivalue` is added to `inf`.
+    pub(crate) fn merge_inf(inf: i64, ivalue: i64) -> i64 {
+        if inf == 0 {
+            // no previous Inf
+            ivalue
+        } else if inf != ivalue {
+            // previous Inf was opposite sign
+            let mut fltv: f64 = f64::from_bits(ivalue as u64);
+            fltv -= fltv; // result will be a NaN
+            fltv.to_bits() as i64
+        } else {
+            inf
+        }
+    }
+
+    /// Returns what `m_nan` becomes when the NaN `ivalue` is added to `nan`.
+    pub(crate) const fn merge_nan(nan: i64, ivalue: i64) -> i64 {
+        // Choose the NaN with the bigger payload and clear its sign.
+        // Using <= ensures that we will choose the first NaN over the previous zero.
+        if (nan & XSUM_MANTISSA_MASK) <= (ivalue & XSUM_MANTISSA_MASK) {
+            ivalue & !XSUM_SIGN_MASK
         } else {
-            // NaN
-            // Choose the NaN with the bigger payload and clear its sign.
-            // Using <= ensures that we will choose the first NaN over the previous zero.
-            if (self.m_nan & XSUM_MANTISSA_MASK) <= mantissa {
-                self.m_nan = ivalue & !XSUM_SIGN_MASK;
+            nan
+        }
+    }
+
+    /// Adds `value`, counting it and propagating carries when needed.
+    #[inline(always)]
+    pub(crate) fn add_value(&mut self, value: f64) {
+        self.increment_when_value_added(value);
+        if self.m_adds_until_propagate == 0 {
+            self.carry_propagate();
+        }
+        self.add1_no_carry(value);
+        self.m_adds_until_propagate -= 1;
+    }
+
+    /// Adds the value of `other`, including its NaN and infinities, exactly.
+    pub(crate) fn add_accumulator(&mut self, other: &Self) {
+        // With carries propagated on both sides, every chunk fits in 33 bits
+        // with its sign, so adding them counts as a single add.
+        let mut other: Self = Self::new_based_on(other);
+        other.carry_propagate();
+        self.carry_propagate();
+        for (chunk, &other_chunk) in self.m_chunk.iter_mut().zip(&other.m_chunk) {
+            *chunk += other_chunk;
+        }
+        self.m_adds_until_propagate -= 1;
+
+        if other.m_nan != 0 {
+            self.add_inf_nan(other.m_nan);
+        }
+        if other.m_inf & XSUM_MANTISSA_MASK != 0 {
+            // +Inf and -Inf were both added to other
+            self.m_inf = other.m_inf;
+        } else if other.m_inf != 0 {
+            self.add_inf_nan(other.m_inf);
+        }
+        if other.m_overflow != 0 {
+            self.set_overflow(other.m_overflow < 0);
+        }
+        self.m_size_count = self.m_size_count.saturating_add(other.m_size_count);
+        self.m_has_pos_number = self.m_has_pos_number || other.m_has_pos_number;
+    }
+
+    /// Handles an Inf or NaN input according to the policy. Unlike `add_inf_nan`,
+    /// this is only for values that were counted by `increment_when_value_added`.
+    #[cold]
+    pub(crate) fn add_special_value(&mut self, ivalue: i64) {
+        let is_nan: bool = ivalue & XSUM_MANTISSA_MASK != 0;
+        match self.m_policy {
+            SpecialValuePolicy::Propagate => self.add_inf_nan(ivalue),
+            SpecialValuePolicy::IgnoreNaN => {
+                if is_nan {
+                    self.m_size_count -= 1;
+                } else {
+                    self.add_inf_nan(ivalue);
+                }
+            }
+            SpecialValuePolicy::IgnoreNonFinite => self.m_size_count -= 1,
+            SpecialValuePolicy::FirstNaN => {
+                if !is_nan || self.m_nan == 0 {
+                    self.add_inf_nan(ivalue);
+                }
+            }
+            SpecialValuePolicy::Error => {
+                // recorded for try_sum(), but not part of the sum
+                self.m_size_count -= 1;
+                self.add_inf_nan(ivalue);
             }
         }
     }
@@ -192,7 +303,7 @@ impl SmallAccumulator {
         } else if exp == XSUM_EXP_MASK {
             // Inf or NaN
             // Just update flags in accumulator structure.
-            self.add_inf_nan(ivalue);
+            self.add_special_value(ivalue);
             return;
         } else {
             // normalized
@@ -223,9 +334,457 @@ impl SmallAccumulator {
         }
     }
 
+    /// Adds `magnitude` * 2^(`offset` - 1075), negated if `negative`, to the chunks.
+    ///
+    /// Every chunk gets less than 2^32, so this counts as a single add towards carry
+    /// propagation, like `add1_no_carry`. A value too large for the chunks on its own
+    /// is recorded as an overflow of its sign instead, as in `set_fixed_point`.
+    pub(crate) fn add_shifted(&mut self, negative: bool, magnitude: u128, offset: i64) {
+        let mut i: usize = (offset >> XSUM_LOW_EXP_BITS) as usize;
+        let shift: i64 = offset & XSUM_LOW_EXP_MASK;
+
+        // The lowest chunk takes the bits below its 32-bit boundary, the chunks above take
+        // 32 bits each, and the top chunk takes the rest.
+        let low_bits: i64 = XSUM_LOW_MANTISSA_BITS - shift;
+        let mut piece: i64 = ((magnitude & ((1 << low_bits) - 1)) << shift) as i64;
+        let mut rest: u128 = magnitude >> low_bits;
+        loop {
+            if negative {
+                self.m_chunk[i] -= piece;
+            } else {
+                self.m_chunk[i] += piece;
+            }
+            if rest == 0 {
+                return;
+            }
+            i += 1;
+            if i == XSUM_SCHUNKS as usize - 1 {
+                if rest > XSUM_LOW_MANTISSA_MASK as u128 {
+                    self.set_overflow(negative);
+                    return;
+                }
+                piece = rest as i64;
+                rest = 0;
+            } else {
+                piece = (rest as i64) & XSUM_LOW_MANTISSA_MASK;
+                rest >>= XSUM_LOW_MANTISSA_BITS;
+            }
+        }
+    }
+
+    /// Adds the integer `magnitude`, negated if `negative`, exactly.
+    pub(crate) fn add_integer(&mut self, negative: bool, magnitude: u128) {
+        self.m_size_count = self.m_size_count.saturating_add(1);
+        // An integer zero is an unsigned zero, which decides a zero sum like +0.0 does.
+        self.m_has_pos_number = self.m_has_pos_number || !negative || magnitude == 0;
+        if magnitude == 0 {
+            return;
+        }
+
+        if self.m_adds_until_propagate == 0 {
+            self.carry_propagate();
+        }
+        // The units bit lies at 2^0 = 2^(1075 - 1075).
+        self.add_shifted(negative, magnitude, -XSUM_FIXED_POINT_EXP);
+        self.m_adds_until_propagate -= 1;
+    }
+
+    /// Adds the value of `fixed_point`, which must fit in the chunks, exactly.
+    pub(crate) fn add_fixed_point(&mut self, fixed_point: &FixedPoint) {
+        self.m_size_count = self.m_size_count.saturating_add(1);
+        self.m_has_pos_number = self.m_has_pos_number || !fixed_point.negative;
+        if fixed_point.is_zero() {
+            return;
+        }
+
+        if self.m_adds_until_propagate == 0 {
+            self.carry_propagate();
+        }
+        // Digit k lines up with chunk k and is less than 2^32, so this is a single add.
+        debug_assert!(
+            fixed_point.digits.len() <= self.m_chunk.len(),
+            "value too large for the chunks"
+        );
+        for (chunk, &digit) in self.m_chunk.iter_mut().zip(&fixed_point.digits) {
+            if fixed_point.negative {
+                *chunk -= i64::from(digit);
+            } else {
+                *chunk += i64::from(digit);
+            }
+        }
+        self.m_adds_until_propagate -= 1;
+    }
+
+    /// Adds a 16-bit floating-point number from its raw bits, with a 1-bit sign, then the
+    /// exponent, then `mantissa_bits` bits of mantissa, as in binary16 and bfloat16.
+    pub(crate) fn add_half_bits(&mut self, bits: u16, mantissa_bits: i64) {
+        let exp_bits: i64 = 15 - mantissa_bits;
+        let exp_mask: i64 = (1 << exp_bits) - 1;
+        let negative: bool = bits >> 15 != 0;
+        let exp: i64 = (i64::from(bits) >> mantissa_bits) & exp_mask;
+        let mut mantissa: i64 = i64::from(bits) & ((1 << mantissa_bits) - 1);
+
+        self.m_size_count = self.m_size_count.saturating_add(1);
+        if exp == exp_mask {
+            // Inf or NaN, handled as the f64 with the same sign and payload
+            let sign: i64 = if negative { XSUM_SIGN_MASK } else { 0 };
+            self.add_special_value(
+                sign | (XSUM_EXP_MASK << XSUM_MANTISSA_BITS)
+                    | (mantissa << (XSUM_MANTISSA_BITS - mantissa_bits)),
+            );
+            return;
+        }
+        self.m_has_pos_number = self.m_has_pos_number || !negative;
+
+        if exp == 0 {
+            // zero or denormalized
+            if mantissa == 0 {
+                return;
+            }
+        } else {
+            mantissa |= 1 << mantissa_bits;
+        }
+
+        if self.m_adds_until_propagate == 0 {
+            self.carry_propagate();
+        }
+        // Denormalized numbers have exponent 1, like the smallest normalized ones.
+        let bias: i64 = (1 << (exp_bits - 1)) - 1;
+        self.add_shifted(
+            negative,
+            mantissa as u128,
+            exp.max(1) - bias - mantissa_bits - XSUM_FIXED_POINT_EXP,
+        );
+        self.m_adds_until_propagate -= 1;
+    }
+
+    /// Returns the raw bits of `value` in the 16-bit format of `add_half_bits`. The value must
+    /// be a NaN, an infinity, or a number that the format holds exactly.
+    pub(crate) fn half_bits_of(value: f64, mantissa_bits: i64) -> u16 {
+        let exp_bits: i64 = 15 - mantissa_bits;
+        let exp_mask: i64 = (1 << exp_bits) - 1;
+        let ivalue: i64 = value.to_bits() as i64;
+        let sign: i64 = if ivalue < 0 { 1 << 15 } else { 0 };
+        let exp: i64 = (ivalue >> XSUM_MANTISSA_BITS) & XSUM_EXP_MASK;
+        let mantissa: i64 = ivalue & XSUM_MANTISSA_MASK;
+
+        if exp == XSUM_EXP_MASK {
+            // Inf or NaN, keeping the upper bits of the payload, and a NaN a NaN
+            let mut payload: i64 = mantissa >> (XSUM_MANTISSA_BITS - mantissa_bits);
+            if mantissa != 0 && payload == 0 {
+                payload = 1 << (mantissa_bits - 1);
+            }
+            return (sign | (exp_mask << mantissa_bits) | payload) as u16;
+        }
+        if exp == 0 {
+            return sign as u16; // zero, since any other f64 subnormal is too small
+        }
+
+        let half_exp: i64 = exp - XSUM_EXP_BIAS + (1 << (exp_bits - 1)) - 1;
+        let bits: i64 = if half_exp >= 1 {
+            (half_exp << mantissa_bits) | (mantissa >> (XSUM_MANTISSA_BITS - mantissa_bits))
+        } else {
+            // denormalized, with the implicit 1 bit shifted into the mantissa
+            (mantissa | (1 << XSUM_MANTISSA_BITS))
+                >> (XSUM_MANTISSA_BITS - mantissa_bits + 1 - half_exp)
+        };
+        (sign | bits) as u16
+    }
+
+    /// Adds `value` `count` times, with one multiplication of its mantissa.
+    pub(crate) fn add_repeated(&mut self, value: f64, count: u64) {
+        if count == 0 {
+            return;
+        }
+
+        // Count the value once, which the policy may undo, and then the other repeats.
+        let size_count: usize = self.m_size_count;
+        self.increment_when_value_added(value);
+
+        let ivalue: i64 = value.to_bits() as i64;
+        let exp: i64 = (ivalue >> XSUM_MANTISSA_BITS) & XSUM_EXP_MASK;
+        let mut mantissa: i64 = ivalue & XSUM_MANTISSA_MASK;
+        if exp == XSUM_EXP_MASK {
+            self.add_special_value(ivalue);
+        } else if exp != 0 || mantissa != 0 {
+            if exp != 0 {
+                mantissa |= 1i64 << XSUM_MANTISSA_BITS;
+            }
+            if self.m_adds_until_propagate == 0 {
+                self.carry_propagate();
+            }
+            // Denormalized numbers have exponent 1, like the smallest normalized ones.
+            self.add_shifted(ivalue < 0, mantissa as u128 * u128::from(count), exp.max(1));
+            self.m_adds_until_propagate -= 1;
+        }
+
+        if self.m_size_count > size_count {
+            self.m_size_count =
+                size_count.saturating_add(usize::try_from(count).unwrap_or(usize::MAX));
+        }
+    }
+
+    /// Returns the sum of the NaN and infinite inputs that the policy keeps in the sum, if any,
+    /// or else the infinity of the sign of a value that overflowed the chunks.
+    /// A NaN input wins over infinities, and `+Inf` with `-Inf` gives NaN.
+    pub(crate) fn special_value(&self) -> Option<f64> {
+        // With the Error policy, NaN and Inf inputs are only recorded for try_sum().
+        if self.m_policy != SpecialValuePolicy::Error {
+            if self.m_nan != 0 {
+                return Some(f64::from_bits(self.m_nan as u64));
+            }
+            if self.m_inf != 0 {
+                return Some(f64::from_bits(self.m_inf as u64));
+            }
+        }
+        if self.m_overflow != 0 {
+            return Some(f64::from_bits(self.m_overflow as u64));
+        }
+        None
+    }
+
+    /// Returns `special_value()` after propagating carries, which are left alone when it is
+    /// already known. A carry out of the top chunk is recorded as an overflow, as for `sum()`.
+    pub(crate) fn carried_special_value(&mut self) -> Option<f64> {
+        if self.special_value().is_none() {
+            self.carry_propagate();
+        }
+        self.special_value()
+    }
+
+    /// Returns the zero to report when the finite sum is exactly zero: `+0.0` if a positive
+    /// number was added, otherwise `-0.0`, as IEEE addition of the inputs would give.
+    pub(crate) const fn signed_zero(&self) -> f64 {
+        if self.m_has_pos_number {
+            0.0
+        } else {
+            -0.0
+        }
+    }
+
+    /// Returns the exact finite value as sign and magnitude, leaving the chunks untouched.
+    pub(crate) fn to_fixed_point(&self) -> FixedPoint {
+        FixedPoint::from_chunks(&self.m_chunk)
+    }
+
+    /// Replaces the finite value with `fixed_point`, or records an overflow of its sign
+    /// if it is too large for the chunks.
+    pub(crate) fn set_fixed_point(&mut self, fixed_point: &FixedPoint) {
+        self.m_chunk.fill(0);
+        self.m_adds_until_propagate = XSUM_SMALL_CARRY_TERMS - 1;
+
+        if fixed_point.digits.len() > XSUM_SCHUNKS as usize {
+            self.set_overflow(fixed_point.negative);
+            return;
+        }
+
+        for (chunk, &digit) in self.m_chunk.iter_mut().zip(&fixed_point.digits) {
+            *chunk = if fixed_point.negative {
+                -i64::from(digit)
+            } else {
+                i64::from(digit)
+            };
+        }
+    }
+
+    /// Multiplies the value by 2^`k`. This is exact unless bits would fall below the smallest
+    /// subnormal, in which case only those bits are rounded away, to the nearest multiple of
+    /// 2^-1074, ties to even. Every bit above them is kept.
+    pub(crate) fn scale_pow2(&mut self, k: i64) {
+        let fixed_point: FixedPoint = self.to_fixed_point();
+        if fixed_point.is_zero() {
+            return;
+        }
+
+        // Bit 1 of the fixed point is the smallest subnormal, 2^-1074.
+        let scaled: FixedPoint = if fixed_point.any_bits_below(1 - k) {
+            let subnormals = FixedPoint {
+                negative: fixed_point.negative,
+                digits: fixed_point.round_to_bit(1 - k, RoundingMode::HalfEven),
+            };
+            subnormals.shifted(1)
+        } else {
+            fixed_point.shifted(k)
+        };
+
+        if scaled.is_zero() {
+            // underflow keeps the sign, as for ldexp
+            self.m_has_pos_number = !scaled.negative;
+        }
+        self.set_fixed_point(&scaled);
+    }
+
+    /// Multiplies the value by `n` exactly.
+    pub(crate) fn mul_int(&mut self, n: i64) {
+        // Inf * 0 is NaN, and a negative factor flips the sign of an infinity
+        if self.m_inf != 0 && self.m_inf & XSUM_MANTISSA_MASK == 0 {
+            if n == 0 {
+                self.m_inf = f64::NAN.to_bits() as i64;
+            } else if n < 0 {
+                self.m_inf ^= XSUM_SIGN_MASK;
+            }
+        }
+        // An overflowed value stays overflowed, with the sign of the product.
+        if self.m_overflow != 0 && n < 0 {
+            self.m_overflow ^= XSUM_SIGN_MASK;
+        }
+
+        let mut fixed_point: FixedPoint = self.to_fixed_point();
+        if fixed_point.is_zero() {
+            // the sign of a zero sum flips with the sign of the factor
+            if n < 0 {
+                self.m_has_pos_number = !self.m_has_pos_number;
+            }
+            return;
+        }
+
+        fixed_point.mul_u64(n.unsigned_abs());
+        fixed_point.negative ^= n < 0;
+        // A non-zero value times 0 is a zero of the same sign, and any later
+        // cancellation of a non-zero value to zero gives +0.
+        self.m_has_pos_number = if fixed_point.is_zero() {
+            !fixed_point.negative
+        } else {
+            true
+        };
+        self.set_fixed_point(&fixed_point);
+    }
+
+    /// Returns a copy whose value is the exact difference between this value and
+    /// the finite number `value`, with `-value` counted as an added value.
+    pub(crate) fn minus(&self, value: f64) -> Self {
+        let mut diff: Self = Self::new_based_on(self);
+        diff.add_value(-value);
+        diff
+    }
+
+    /// Returns |value - `candidate`| in units of the last place of the finite `candidate`,
+    /// rounded down, or `u64::MAX` if that is larger.
+    pub(crate) fn ulps_from(&self, candidate: f64) -> u64 {
+        // Bit `exp` of the fixed point is the last place of the candidate, with the
+        // exponent of the smallest normal number for subnormals.
+        let exp: i64 = ((candidate.to_bits() >> XSUM_MANTISSA_BITS) as i64 & XSUM_EXP_MASK).max(1);
+        let units: FixedPoint = self.minus(candidate).to_fixed_point().shifted(-exp);
+        FixedPoint::integer_to_u128(&units.digits)
+            .and_then(|units| u64::try_from(units).ok())
+            .unwrap_or(u64::MAX)
+    }
+
+    /// Returns `true` if the finite part of the value is exactly zero.
+    pub(crate) fn is_zero(&mut self) -> bool {
+        let i: i32 = self.carry_propagate();
+        self.m_chunk[i as usize] == 0
+    }
+
+    /// Returns the sign of the finite part of the value: -1, 0 or 1.
+    pub(crate) fn signum(&mut self) -> i64 {
+        let i: i32 = self.carry_propagate();
+        self.m_chunk[i as usize].signum()
+    }
+
+    /// Returns the sign of the value as `XsumSmall::signum` does: -1.0, 0.0 or 1.0,
+    /// the sign of an infinity, or NaN.
+    pub(crate) fn value_signum(&mut self) -> f64 {
+        if let Some(special) = self.carried_special_value() {
+            return if special.is_nan() {
+                special
+            } else {
+                special.signum()
+            };
+        }
+        self.signum() as f64
+    }
+
+    /// Returns where the value falls in the total order of `total_cmp`:
+    /// 0 for `-Inf`, 1 for finite values, 2 for `+Inf` and 3 for NaN.
+    fn order_rank(&self) -> u8 {
+        match self.special_value() {
+            None => 1,
+            Some(special) if special.is_nan() => 3,
+            Some(special) if special < 0.0 => 0,
+            Some(_) => 2,
+        }
+    }
+
+    /// Compares the values exactly, leaving the chunks untouched. All NaNs are equal
+    /// and greater than `+Inf`, and the sign of a zero is ignored.
+    pub(crate) fn total_cmp(&self, other: &Self) -> Ordering {
+        let rank: u8 = self.order_rank();
+        rank.cmp(&other.order_rank()).then_with(|| {
+            if rank == 1 {
+                self.to_fixed_point().cmp_value(&other.to_fixed_point())
+            } else {
+                Ordering::Equal
+            }
+        })
+    }
+
+    /// Compares the value exactly with `value`, or returns `None` if either is NaN.
+    pub(crate) fn cmp_f64(&self, value: f64) -> Option<Ordering> {
+        match self.special_value() {
+            Some(special) => special.partial_cmp(&value),
+            // any finite value compares with an infinity or NaN as zero does
+            None if !value.is_finite() => 0.0.partial_cmp(&value),
+            None => Some(
+                self.to_fixed_point()
+                    .cmp_value(&FixedPoint::from_f64(value)),
+            ),
+        }
+    }
+
+    /// Feeds the value to `state` so that values equal under `total_cmp` hash alike.
+    pub(crate) fn hash_value<H: Hasher>(&self, state: &mut H) {
+        let rank: u8 = self.order_rank();
+        rank.hash(state);
+        if rank == 1 {
+            let fixed_point: FixedPoint = self.to_fixed_point();
+            // +0 and -0 are equal, and both have no digits
+            (fixed_point.negative && !fixed_point.is_zero()).hash(state);
+            fixed_point.digits.hash(state);
+        }
+    }
+
+    /// Appends the inf/nan state and the non-zero chunks, lowest first, to `out`.
+    /// Chunk `i` holds a multiple of 2^(32i - 1075); its low-order 32 bits cover
+    /// the exponents printed next to it.
+    pub(crate) fn write_dump(&self, out: &mut String) {
+        let _ = writeln!(
+            out,
+            "small accumulator: {} values, {} adds until propagate",
+            self.m_size_count, self.m_adds_until_propagate
+        );
+        if self.m_inf != 0 {
+            let _ = writeln!(out, "  inf: {}", f64::from_bits(self.m_inf as u64));
+        }
+        if self.m_nan != 0 {
+            let _ = writeln!(out, "  nan: payload {:#x}", self.m_nan & XSUM_MANTISSA_MASK);
+        }
+        if self.m_overflow != 0 {
+            let _ = writeln!(
+                out,
+                "  overflow: {}",
+                f64::from_bits(self.m_overflow as u64)
+            );
+        }
+        for (i, &chunk) in self.m_chunk.iter().enumerate() {
+            if chunk == 0 {
+                continue;
+            }
+            let low: i64 = ((i as i64) << XSUM_LOW_EXP_BITS) - XSUM_EXP_BIAS - XSUM_MANTISSA_BITS;
+            let _ = writeln!(
+                out,
+                "  chunk[{i:2}] = {chunk:#018x} ({chunk}) x 2^{low}, exponents {low}..{}",
+                low + XSUM_LOW_MANTISSA_BITS - 1
+            );
+        }
+    }
+
     #[inline(always)]
     pub(crate) fn increment_when_value_added(&mut self, value: f64) {
-        self.m_size_count += 1;
-        self.m_has_pos_number = self.m_has_pos_number || value.is_sign_positive();
+        self.m_size_count = self.m_size_count.saturating_add(1);
+        // Inf and NaN decide the sign of the sum themselves, unless the policy leaves them out,
+        // in which case they must not decide the sign of a zero sum either.
+        self.m_has_pos_number =
+            self.m_has_pos_number || (value.is_sign_positive() && value.is_finite());
     }
 }
@@ -0,0 +1,355 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicI64, AtomicUsize, Ordering::SeqCst},
+    PoisonError, RwLock,
+};
+
+use crate::{
+    accumulators::small_accumulator::SmallAccumulator,
+    constants::{
+        XSUM_CONCURRENT_CARRY_LIMIT, XSUM_EXP_MASK, XSUM_LOW_EXP_BITS, XSUM_LOW_EXP_MASK,
+        XSUM_LOW_MANTISSA_BITS, XSUM_LOW_MANTISSA_MASK, XSUM_MANTISSA_BITS, XSUM_MANTISSA_MASK,
+        XSUM_SCHUNKS, XSUM_SIGN_MASK,
+    },
+    policy::SpecialValuePolicy,
+    Xsum, XsumSmall,
+};
+
+/// ConcurrentXsum is an exact sum that many threads can add to at once
+///
+/// It holds the chunks of a small accumulator as atomic integers, so `add()` takes `&self`
+/// and updates them with compare-and-swaps, in parallel with the adds of other threads.
+/// A chunk is carried into the next one when it grows past a quarter of its range.
+///
+/// Adds and sums block each other through a `RwLock`: adds share it, while `sum()` takes it
+/// alone, so it waits for the adds in progress and new adds wait until it has read the
+/// chunks. It then rounds them exactly like `XsumSmall`. NaN and infinities are handled as
+/// with the default policy.
+///
+/// # Example
+///
+/// ```
+/// use std::thread;
+///
+/// use xsum::ConcurrentXsum;
+///
+/// static TOTAL: ConcurrentXsum = ConcurrentXsum::new();
+///
+/// thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             for _ in 0..1_000 {
+///                 TOTAL.add(0.1);
+///             }
+///         });
+///     }
+/// });
+/// assert_eq!(TOTAL.sum(), 400.0);
+/// assert_eq!(TOTAL.len(), 4_000);
+/// ```
+#[cfg_attr(debug_assertions, derive(Debug))]
+pub struct ConcurrentXsum {
+    m_chunk: [AtomicI64; XSUM_SCHUNKS as usize], // Chunks of a small accumulator, carried lazily
+    m_inf: AtomicI64,                            // If non-zero, +Inf, -Inf, or NaN
+    m_nan: AtomicI64,                            // If non-zero, a NaN value with payload
+    m_overflow: AtomicI64, // If non-zero, the infinity the chunks overflowed toward
+    m_size_count: AtomicUsize, // number of added values
+    m_has_pos_number: AtomicBool, // check if added values have at least one positive finite number
+    m_lock: RwLock<()>,    // shared by adds, taken alone by snapshots
+}
+
+impl Default for ConcurrentXsum {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConcurrentXsum {
+    /// Creates an empty sum, which can be a `static`
+    ///
+    /// ```
+    /// use xsum::ConcurrentXsum;
+    ///
+    /// static TOTAL: ConcurrentXsum = ConcurrentXsum::new();
+    /// assert_eq!(TOTAL.sum(), -0.0);
+    /// ```
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            m_chunk: [const { AtomicI64::new(0) }; XSUM_SCHUNKS as usize],
+            m_inf: AtomicI64::new(0),
+            m_nan: AtomicI64::new(0),
+            m_overflow: AtomicI64::new(0),
+            m_size_count: AtomicUsize::new(0),
+            m_has_pos_number: AtomicBool::new(false),
+            m_lock: RwLock::new(()),
+        }
+    }
+
+    /// Adds `value`
+    ///
+    /// ```
+    /// use xsum::ConcurrentXsum;
+    ///
+    /// let total = ConcurrentXsum::new();
+    /// total.add(1e100);
+    /// total.add(1.0);
+    /// total.add(-1e100);
+    /// assert_eq!(total.sum(), 1.0);
+    /// ```
+    pub fn add(&self, value: f64) {
+        let _adding = self.m_lock.read().unwrap_or_else(PoisonError::into_inner);
+        self.add_value(value);
+    }
+
+    /// Adds each value of `vec`
+    ///
+    /// The values are added as one update: `sum()` sees all of them or none, and waits
+    /// until they are all added.
+    ///
+    /// ```
+    /// use xsum::ConcurrentXsum;
+    ///
+    /// let total = ConcurrentXsum::new();
+    /// total.add_list(&[1e100, 1.0, -1e100]);
+    /// assert_eq!(total.sum(), 1.0);
+    /// ```
+    pub fn add_list(&self, vec: &[f64]) {
+        let _adding = self.m_lock.read().unwrap_or_else(PoisonError::into_inner);
+        for &value in vec {
+            self.add_value(value);
+        }
+    }
+
+    /// Returns the sum of the values added so far, rounded once to the nearest `f64`
+    ///
+    /// The sum is exact at a moment between the call and its return when no add was in
+    /// progress. This blocks until the adds that started before the call are done, and
+    /// adds that start during it block until the chunks have been read.
+    ///
+    /// ```
+    /// use xsum::ConcurrentXsum;
+    ///
+    /// let total = ConcurrentXsum::new();
+    /// total.add_list(&[0.1, 0.2, 0.3]);
+    /// assert_eq!(total.sum(), 0.6);
+    /// ```
+    #[must_use]
+    pub fn sum(&self) -> f64 {
+        self.snapshot().sum()
+    }
+
+    /// Returns an `XsumSmall` holding the values added so far, read as in `sum()`
+    ///
+    /// ```
+    /// use xsum::ConcurrentXsum;
+    ///
+    /// let total = ConcurrentXsum::new();
+    /// total.add_list(&[1.0, 2.0_f64.powi(-80)]);
+    /// assert_eq!(total.snapshot().to_hex_string(), "0x1.00000000000000000001p+0");
+    /// ```
+    #[must_use]
+    pub fn snapshot(&self) -> XsumSmall {
+        let mut sacc: SmallAccumulator =
+            SmallAccumulator::with_policy(SpecialValuePolicy::Propagate);
+        {
+            // No add is in progress while this is held, so the chunks hold still.
+            let _reading = self.m_lock.write().unwrap_or_else(PoisonError::into_inner);
+            for (chunk, atomic_chunk) in sacc.m_chunk.iter_mut().zip(&self.m_chunk) {
+                *chunk = atomic_chunk.load(SeqCst);
+            }
+            sacc.m_inf = self.m_inf.load(SeqCst);
+            sacc.m_nan = self.m_nan.load(SeqCst);
+            sacc.m_overflow = self.m_overflow.load(SeqCst);
+            sacc.m_size_count = self.m_size_count.load(SeqCst);
+            sacc.m_has_pos_number = self.m_has_pos_number.load(SeqCst);
+        }
+
+        sacc.carry_propagate();
+        XsumSmall::new_with(&sacc)
+    }
+
+    /// Returns the number of values added so far
+    ///
+    /// ```
+    /// use xsum::ConcurrentXsum;
+    ///
+    /// let total = ConcurrentXsum::new();
+    /// total.add_list(&[1.0, f64::NAN]);
+    /// assert_eq!(total.len(), 2);
+    /// ```
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.m_size_count.load(SeqCst)
+    }
+
+    /// Returns `true` if no value has been added
+    ///
+    /// ```
+    /// use xsum::ConcurrentXsum;
+    ///
+    /// let total = ConcurrentXsum::new();
+    /// assert!(total.is_empty());
+    /// total.add(0.0);
+    /// assert!(!total.is_empty());
+    /// ```
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Adds `value` to the chunks as `SmallAccumulator::add1_no_carry` does, or records it
+    // as Inf or NaN.
+    fn add_value(&self, value: f64) {
+        self.m_size_count.fetch_add(1, SeqCst);
+        if value.is_sign_positive() && value.is_finite() {
+            self.m_has_pos_number.store(true, SeqCst);
+        }
+
+        let ivalue: i64 = value.to_bits() as i64;
+        let exp: i64 = (ivalue >> XSUM_MANTISSA_BITS) & XSUM_EXP_MASK;
+        let mut mantissa: i64 = ivalue & XSUM_MANTISSA_MASK;
+        let high_exp: usize = (exp >> XSUM_LOW_EXP_BITS) as usize;
+        let mut low_exp: i64 = exp & XSUM_LOW_EXP_MASK;
+
+        if exp == 0 {
+            // zero or denormalized
+            if mantissa == 0 {
+                return;
+            }
+            low_exp = 1;
+        } else if exp == XSUM_EXP_MASK {
+            self.add_inf_nan(ivalue);
+            return;
+        } else {
+            // normalized
+            mantissa |= 1i64 << XSUM_MANTISSA_BITS;
+        }
+
+        let mut split_mantissa: [i64; 2] = [
+            (mantissa << low_exp) & XSUM_LOW_MANTISSA_MASK,
+            mantissa >> (XSUM_LOW_MANTISSA_BITS - low_exp),
+        ];
+        if ivalue < 0 {
+            split_mantissa = [-split_mantissa[0], -split_mantissa[1]];
+        }
+        self.add_to_chunk(high_exp, split_mantissa[0]);
+        self.add_to_chunk(high_exp + 1, split_mantissa[1]);
+    }
+
+    // Adds `delta`, less than 2^XSUM_MANTISSA_BITS in magnitude, to chunk `i`. A chunk past
+    // the carry limit is carried into the next one first, so however many threads add at
+    // once, no chunk gets near the range of i64.
+    fn add_to_chunk(&self, i: usize, delta: i64) {
+        let mut chunk: i64 = self.m_chunk[i].load(SeqCst);
+        loop {
+            let carrying: bool = chunk.abs() >= XSUM_CONCURRENT_CARRY_LIMIT;
+            if carrying && i + 1 == XSUM_SCHUNKS as usize {
+                self.set_overflow(chunk < 0);
+                return;
+            }
+            let new_chunk: i64 = if carrying {
+                chunk & XSUM_LOW_MANTISSA_MASK
+            } else {
+                chunk + delta
+            };
+            // Only the thread whose swap succeeds moves this carry, so it moves once.
+            match self.m_chunk[i].compare_exchange(chunk, new_chunk, SeqCst, SeqCst) {
+                Ok(_) if carrying => {
+                    // The carry is less than 2^31 in magnitude, and delta is added after it.
+                    let carry: i64 = chunk >> XSUM_LOW_MANTISSA_BITS;
+                    self.add_to_chunk(i + 1, carry);
+                    chunk = self.m_chunk[i].load(SeqCst);
+                }
+                Ok(_) => return,
+                Err(current) => chunk = current,
+            }
+        }
+    }
+
+    // Records that the chunks overflowed, as `SmallAccumulator::set_overflow` does.
+    #[cold]
+    fn set_overflow(&self, negative: bool) {
+        let sign: i64 = if negative { XSUM_SIGN_MASK } else { 0 };
+        let _ = self.m_overflow.compare_exchange(
+            0,
+            sign | (XSUM_EXP_MASK << XSUM_MANTISSA_BITS),
+            SeqCst,
+            SeqCst,
+        );
+    }
+
+    #[cold]
+    fn add_inf_nan(&self, ivalue: i64) {
+        let (target, merge): (&AtomicI64, fn(i64, i64) -> i64) = if ivalue & XSUM_MANTISSA_MASK == 0
+        {
+            (&self.m_inf, SmallAccumulator::merge_inf)
+        } else {
+            (&self.m_nan, SmallAccumulator::merge_nan)
+        };
+        let mut current: i64 = target.load(SeqCst);
+        loop {
+            match target.compare_exchange(current, merge(current, ivalue), SeqCst, SeqCst) {
+                Ok(_) => return,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+}
+
+impl Xsum for ConcurrentXsum {
+    /// ```
+    /// use xsum::{ConcurrentXsum, Xsum};
+    ///
+    /// let mut total = <ConcurrentXsum as Xsum>::new();
+    /// assert_eq!(Xsum::sum(&mut total), -0.0);
+    /// ```
+    fn new() -> Self {
+        Self::new()
+    }
+
+    /// ```
+    /// use xsum::{ConcurrentXsum, Xsum};
+    ///
+    /// let mut total = ConcurrentXsum::new();
+    /// Xsum::add_list(&mut total, &[1.0, 2.0, 3.0]);
+    /// assert_eq!(total.sum(), 6.0);
+    /// ```
+    fn add_list(&mut self, vec: &[f64]) {
+        Self::add_list(self, vec);
+    }
+
+    /// ```
+    /// use xsum::{ConcurrentXsum, Xsum};
+    ///
+    /// let mut total = ConcurrentXsum::new();
+    /// Xsum::add(&mut total, 1.0);
+    /// assert_eq!(total.sum(), 1.0);
+    /// ```
+    fn add(&mut self, value: f64) {
+        Self::add(self, value);
+    }
+
+    /// ```
+    /// use xsum::{ConcurrentXsum, Xsum};
+    ///
+    /// let mut total = ConcurrentXsum::new();
+    /// total.add_list(&[1.0, 2.0, 3.0]);
+    /// assert_eq!(Xsum::sum(&mut total), 6.0);
+    /// ```
+    fn sum(&mut self) -> f64 {
+        Self::sum(self)
+    }
+
+    /// ```
+    /// use xsum::{ConcurrentXsum, Xsum};
+    ///
+    /// let mut total = ConcurrentXsum::new();
+    /// total.add_list(&[1.0, 2.0, 3.0]);
+    /// total.clear();
+    /// assert_eq!(total.sum(), -0.0);
+    /// assert!(total.is_empty());
+    /// ```
+    fn clear(&mut self) {
+        *self = Self::new();
+    }
+}
@@ -7,6 +7,10 @@ pub(crate) const XSUM_EXP_BIAS: i64 = (1 << (XSUM_EXP_BITS - 1)) - 1; // Bias ad
 pub(crate) const XSUM_SIGN_BIT: i64 = XSUM_MANTISSA_BITS + XSUM_EXP_BITS; // Position of sign bit
 pub(crate) const XSUM_SIGN_MASK: i64 = 1i64 << XSUM_SIGN_BIT; // Mask for sign bit
 
+// CONSTANTS DEFINING THE 16-BIT INPUT FORMATS
+pub(crate) const XSUM_F16_MANTISSA_BITS: i64 = 10; // Bits in binary16 mantissa, excludes implicit 1
+pub(crate) const XSUM_BF16_MANTISSA_BITS: i64 = 7; // Bits in bfloat16 mantissa, excludes implicit 1
+
 // CONSTANTS DEFINING THE SMALL ACCUMULATOR FORMAT
 pub(crate) const XSUM_SCHUNK_BITS: i64 = 64; // Bits in chunk of the small accumulator
 pub(crate) const XSUM_LOW_EXP_BITS: i64 = 5; // # of low bits of exponent, in one chunk
@@ -18,6 +22,11 @@ pub(crate) const XSUM_LOW_MANTISSA_MASK: i64 = (1i64 << XSUM_LOW_MANTISSA_BITS)
 pub(crate) const XSUM_SMALL_CARRY_BITS: i64 = (XSUM_SCHUNK_BITS - 1) - XSUM_MANTISSA_BITS; // Bits sums can carry into
 pub(crate) const XSUM_SMALL_CARRY_TERMS: i64 = (1 << XSUM_SMALL_CARRY_BITS) - 1; // # terms can add before need prop.
 
+// CONSTANTS DEFINING THE CONCURRENT ACCUMULATOR
+// An add is only made to a chunk below the carry limit, and puts less than 2^XSUM_MANTISSA_BITS
+// in it, so a chunk stays below half of its range.
+pub(crate) const XSUM_CONCURRENT_CARRY_LIMIT: i64 = 1 << (XSUM_SCHUNK_BITS - 3); // Chunk magnitude that is carried up
+
 // CONSTANTS DEFINING THE LARGE ACCUMULATOR FORMAT
 pub(crate) const XSUM_LCOUNT_BITS: i64 = 64 - XSUM_MANTISSA_BITS; // # of bits in count
 pub(crate) const XSUM_LCHUNKS: usize = 1 << (XSUM_EXP_BITS + 1); // # of chunks in large accumulator
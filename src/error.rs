@@ -0,0 +1,42 @@
+use std::{error::Error, fmt};
+
+use crate::sign::Sign;
+
+/// Reason why [`try_sum()`](crate::XsumSmall::try_sum) could not return a finite sum
+///
+/// # Example
+///
+/// ```
+/// use xsum::{Sign, Xsum, XsumError, XsumSmall};
+///
+/// let mut xsmall = XsumSmall::new();
+/// xsmall.add_list(&vec![f64::MAX, f64::MAX]);
+/// assert_eq!(xsmall.try_sum(), Err(XsumError::Overflow { sign: Sign::Positive }));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XsumError {
+    /// A NaN was added. `payload` is the 52-bit mantissa field of the NaN that `sum()` returns.
+    NaNInput { payload: u64 },
+    /// `+Inf` or `-Inf` was added, but not both
+    InfiniteInput { sign: Sign },
+    /// Both `+Inf` and `-Inf` were added
+    OppositeInfinities,
+    /// All inputs are finite, but the exact sum is too large for `f64`, or for the integer
+    /// type asked for
+    Overflow { sign: Sign },
+}
+
+impl fmt::Display for XsumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NaNInput { payload } => write!(f, "NaN was added (payload {payload:#x})"),
+            Self::InfiniteInput { sign } => write!(f, "{sign}Inf was added"),
+            Self::OppositeInfinities => f.write_str("both +Inf and -Inf were added"),
+            Self::Overflow { sign } => write!(f, "exact sum overflows toward {sign}Inf"),
+        }
+    }
+}
+
+// The provided methods of `Error` are deprecated, unstable, or not meant to be overridden.
+#[allow(clippy::missing_trait_methods)]
+impl Error for XsumError {}
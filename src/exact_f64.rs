@@ -0,0 +1,251 @@
+use std::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Neg, Sub, SubAssign},
+};
+
+use crate::{accumulators::small_accumulator::SmallAccumulator, Xsum, XsumSmall};
+
+/// ExactF64 is a value that holds any finite sum of `f64` numbers exactly
+///
+/// It adds, subtracts and negates without rounding, and compares, hashes and sums by its
+/// exact value. `to_f64()` rounds it once, to nearest. Like `sum()`, adding a NaN or an
+/// infinity makes the value NaN or infinite.
+///
+/// # Example
+///
+/// ```
+/// use xsum::ExactF64;
+///
+/// let total: ExactF64 = [0.1, 0.2, -0.3].into_iter().sum();
+/// assert_eq!(0.1 + 0.2 - 0.3, 5.551115123125783e-17);
+/// assert_eq!(total.to_f64(), 2.7755575615628914e-17);
+///
+/// let shifted = total.clone() + 1e300 - 1e300;
+/// assert!(shifted == total);
+/// assert!(-total < ExactF64::zero());
+/// ```
+#[derive(Clone)]
+pub struct ExactF64 {
+    m_sacc: SmallAccumulator,
+}
+
+impl Default for ExactF64 {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl ExactF64 {
+    /// Returns the value zero, which rounds to `-0.0` like an empty sum
+    ///
+    /// ```
+    /// use xsum::ExactF64;
+    ///
+    /// let zero = ExactF64::zero();
+    /// assert!(zero.to_f64().is_sign_negative());
+    /// assert!(zero == ExactF64::from(0.0));
+    /// ```
+    #[must_use]
+    pub fn zero() -> Self {
+        // An added -0.0 rather than an empty sum, so that negation gives +0.0
+        let mut m_sacc: SmallAccumulator = SmallAccumulator::new();
+        m_sacc.add_value(-0.0);
+        Self { m_sacc }
+    }
+
+    /// Returns the value rounded once to the nearest `f64`, ties to even
+    ///
+    /// ```
+    /// use xsum::ExactF64;
+    ///
+    /// let value = ExactF64::from(1.0) + 2.0_f64.powi(-53) + 2.0_f64.powi(-80);
+    /// assert_eq!(value.to_f64(), 1.0000000000000002);
+    /// ```
+    #[must_use]
+    pub fn to_f64(&self) -> f64 {
+        XsumSmall::new_with(&self.m_sacc).sum()
+    }
+
+    /// Returns the value times 2^`k`
+    ///
+    /// This is exact, including beyond the range of `f64`, unless bits would fall below the
+    /// smallest subnormal, in which case only those bits are rounded, to the nearest multiple
+    /// of 2^-1074. See [`XsumSmall::scale_pow2`].
+    ///
+    /// ```
+    /// use xsum::ExactF64;
+    ///
+    /// let value = ExactF64::from(f64::MAX).mul_pow2(1);
+    /// assert_eq!(value.to_f64(), f64::INFINITY);
+    /// assert_eq!(value.mul_pow2(-2).to_f64(), f64::MAX / 2.0);
+    /// ```
+    #[must_use]
+    pub fn mul_pow2(mut self, k: i32) -> Self {
+        self.m_sacc.scale_pow2(i64::from(k));
+        self
+    }
+
+    /// Calls `f` with a small accumulator holding the exact value, for comparisons and hashing
+    pub(crate) fn with_exact<R>(&self, f: impl FnOnce(&SmallAccumulator) -> R) -> R {
+        f(&self.m_sacc)
+    }
+}
+
+/// Shows the exact value in hexadecimal. See [`XsumSmall::to_hex_string`].
+///
+/// ```
+/// use xsum::ExactF64;
+///
+/// let value = ExactF64::from(1.0) + 2.0_f64.powi(-80);
+/// assert_eq!(format!("{value:?}"), "ExactF64(0x1.00000000000000000001p+0)");
+/// ```
+impl fmt::Debug for ExactF64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex: String = XsumSmall::new_with(&self.m_sacc).to_hex_string();
+        write!(f, "ExactF64({hex})")
+    }
+}
+
+impl From<f64> for ExactF64 {
+    fn from(value: f64) -> Self {
+        let mut m_sacc: SmallAccumulator = SmallAccumulator::new();
+        m_sacc.add_value(value);
+        Self { m_sacc }
+    }
+}
+
+impl Add for ExactF64 {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self += &rhs;
+        self
+    }
+}
+
+impl Add<&Self> for ExactF64 {
+    type Output = Self;
+
+    fn add(mut self, rhs: &Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl Add<f64> for ExactF64 {
+    type Output = Self;
+
+    fn add(mut self, rhs: f64) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl AddAssign for ExactF64 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self += &rhs;
+    }
+}
+
+impl AddAssign<&Self> for ExactF64 {
+    fn add_assign(&mut self, rhs: &Self) {
+        self.m_sacc.add_accumulator(&rhs.m_sacc);
+    }
+}
+
+impl AddAssign<f64> for ExactF64 {
+    fn add_assign(&mut self, rhs: f64) {
+        self.m_sacc.add_value(rhs);
+    }
+}
+
+impl Sub for ExactF64 {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self {
+        self += -rhs;
+        self
+    }
+}
+
+impl Sub<&Self> for ExactF64 {
+    type Output = Self;
+
+    fn sub(mut self, rhs: &Self) -> Self {
+        self += -rhs.clone();
+        self
+    }
+}
+
+impl Sub<f64> for ExactF64 {
+    type Output = Self;
+
+    fn sub(mut self, rhs: f64) -> Self {
+        self -= rhs;
+        self
+    }
+}
+
+impl SubAssign for ExactF64 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self += -rhs;
+    }
+}
+
+impl SubAssign<&Self> for ExactF64 {
+    fn sub_assign(&mut self, rhs: &Self) {
+        *self += -rhs.clone();
+    }
+}
+
+impl SubAssign<f64> for ExactF64 {
+    fn sub_assign(&mut self, rhs: f64) {
+        self.m_sacc.add_value(-rhs);
+    }
+}
+
+impl Neg for ExactF64 {
+    type Output = Self;
+
+    fn neg(mut self) -> Self {
+        self.m_sacc.mul_int(-1);
+        self
+    }
+}
+
+impl Sum<f64> for ExactF64 {
+    fn sum<I: Iterator<Item = f64>>(iter: I) -> Self {
+        let mut exact: Self = Self::zero();
+        for value in iter {
+            exact += value;
+        }
+        exact
+    }
+}
+
+impl<'a> Sum<&'a f64> for ExactF64 {
+    fn sum<I: Iterator<Item = &'a f64>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl Sum for ExactF64 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        let mut exact: Self = Self::zero();
+        for value in iter {
+            exact += &value;
+        }
+        exact
+    }
+}
+
+impl<'a> Sum<&'a Self> for ExactF64 {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        let mut exact: Self = Self::zero();
+        for value in iter {
+            exact += value;
+        }
+        exact
+    }
+}
@@ -0,0 +1,23 @@
+/// IEEE 754 style status flags of the rounding done by `sum()`
+///
+/// # Example
+///
+/// ```
+/// use xsum::{Xsum, XsumSmall};
+///
+/// let mut xsmall = XsumSmall::new();
+/// xsmall.add_list(&vec![0.1, 0.2]);
+/// let (sum, flags) = xsmall.sum_with_flags();
+/// assert_eq!(sum, 0.30000000000000004);
+/// assert!(flags.inexact);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SumFlags {
+    /// The returned value differs from the exact sum
+    pub inexact: bool,
+    /// The exact sum of finite inputs is too large for `f64`, so the returned value is infinite
+    pub overflow: bool,
+    /// The returned value is subnormal. Every `f64` is a multiple of the smallest subnormal,
+    /// and so is the exact sum, so a subnormal result is always exact.
+    pub subnormal: bool,
+}
@@ -2,7 +2,8 @@
 //!
 //! xsum is able to calculate fast exact summation
 //!
-//! ⚠️ Currently, xsum supports `f64` calculation only.
+//! ⚠️ Currently, xsum calculates `f64` sums only. Narrower numbers, such as `f32` and `i32`,
+//! can be added through `ExactAddend`.
 //!
 //! # Usage
 //!
@@ -59,18 +60,136 @@
 //! let vec = vec![1.0, 2.0, 3.0];
 //! assert_eq!(vec.xsum(), 6.0);
 //! ```
+//!
+//! ## 16-bit Inputs
+//!
+//! Buffers of binary16 or bfloat16 numbers can be added from their raw bits, without
+//! converting them to `f64` first. With the `half` feature, `half::f16` and `half::bf16`
+//! values can also be passed to `add_generic()` and `add_slice()`.
+//!
+//! ```
+//! use xsum::{Xsum, XsumSmall};
+//!
+//! let mut xsmall = XsumSmall::new();
+//! xsmall.add_f16_bits(&[0x3c00, 0x4000]); // 1.0 and 2.0
+//! xsmall.add_bf16_bits(&[0x4040]); // 3.0
+//! assert_eq!(xsmall.sum(), 6.0);
+//! ```
+//!
+//! ## NaN and Infinity Policy
+//!
+//! By default, NaN and infinities propagate to the sum. A `SpecialValuePolicy` chosen at
+//! construction can skip them instead, like numpy's `nansum`.
+//!
+//! ```
+//! use xsum::{SpecialValuePolicy, Xsum, XsumSmall};
+//!
+//! let mut xsmall = XsumSmall::with_policy(SpecialValuePolicy::IgnoreNaN);
+//! xsmall.add_list(&vec![1.0, f64::NAN, 2.0]);
+//! assert_eq!(xsmall.sum(), 3.0);
+//! assert_eq!(xsmall.len(), 2);
+//! ```
+//!
+//! ## Comparing Sums
+//!
+//! Accumulators compare and hash by their exact value, so they can be used as map keys.
+//! All NaN sums are equal to each other and greater than `+Inf`.
+//!
+//! ```
+//! use std::collections::BTreeSet;
+//! use xsum::{Xsum, XsumSmall};
+//!
+//! let mut a = XsumSmall::new();
+//! a.add_list(&vec![0.1, 0.2, 0.3]);
+//! let mut b = XsumSmall::new();
+//! b.add_list(&vec![0.3, 0.2, 0.1]);
+//! assert_ne!(0.1 + 0.2 + 0.3, 0.3 + 0.2 + 0.1);
+//! assert!(a == b);
+//!
+//! let mut c = XsumSmall::new();
+//! c.add(0.6);
+//! let sums: BTreeSet<XsumSmall> = [a, b, c].into_iter().collect();
+//! assert_eq!(sums.len(), 2);
+//! ```
+//!
+//! ## Diagnostics
+//!
+//! `XsumProfile` sums like the other accumulators, and also reports the condition number
+//! of the sum and how the values are spread over exponents.
+//!
+//! ```
+//! use xsum::{Xsum, XsumProfile};
+//!
+//! let mut xprofile = XsumProfile::new();
+//! xprofile.add_list(&vec![1e16, 1.0, -1e16]);
+//! assert_eq!(xprofile.sum(), 1.0);
+//! assert_eq!(xprofile.condition_number(), 2e16);
+//! ```
+//!
+//! ## Sharing Across Threads
+//!
+//! `ConcurrentXsum` takes `&self` to add, so threads can share one exact total. Adds run in
+//! parallel, while `sum()` blocks them for as long as it takes to read the total.
+//!
+//! ```
+//! use xsum::ConcurrentXsum;
+//!
+//! let total = ConcurrentXsum::new();
+//! std::thread::scope(|scope| {
+//!     scope.spawn(|| total.add_list(&[1e100, 1.0]));
+//!     scope.spawn(|| total.add(-1e100));
+//! });
+//! assert_eq!(total.sum(), 1.0);
+//! ```
+//!
+//! ## Differential Testing
+//!
+//! The `reference` module has a slow, plainly written exact sum to check the accumulators
+//! against, on any input. The `testgen` module generates ill-conditioned data from a seed,
+//! with its exact sum.
+//!
+//! ```
+//! use xsum::{reference::assert_agrees, testgen::TestGen};
+//!
+//! assert_agrees(&[1e308, 1e308, -1e308, 0.1]);
+//!
+//! let data = TestGen::new(1).ill_conditioned(100, 1e30);
+//! assert_agrees(data.values());
+//! ```
 
 mod accumulators;
+mod concurrent_xsum;
+mod error;
+mod exact_f64;
+mod flags;
+mod parse_hex_error;
+mod policy;
+mod rounding_mode;
+mod sign;
 mod traits;
 mod xsum_auto;
 mod xsum_large;
+mod xsum_profile;
 mod xsum_small;
 mod xsum_variant;
 
 pub mod constants;
+pub mod reference;
+pub mod summation;
+pub mod testgen;
+pub use concurrent_xsum::ConcurrentXsum;
+pub use error::XsumError;
+pub use exact_f64::ExactF64;
+pub use flags::SumFlags;
+pub use parse_hex_error::ParseHexError;
+pub use policy::SpecialValuePolicy;
+pub use rounding_mode::RoundingMode;
+pub use sign::Sign;
+pub use traits::ExactAddend;
 pub use traits::Xsum;
 pub use traits::XsumExt;
 pub use xsum_auto::XsumAuto;
 pub use xsum_large::XsumLarge;
+pub use xsum_profile::XsumProfile;
 pub use xsum_small::XsumSmall;
 pub use xsum_variant::XsumVariant;
@@ -0,0 +1,36 @@
+use std::{error::Error, fmt};
+
+/// Reason why [`add_hex_str()`](crate::XsumSmall::add_hex_str) could not add a hexadecimal literal
+///
+/// # Example
+///
+/// ```
+/// use xsum::{ParseHexError, Xsum, XsumSmall};
+///
+/// let mut xsmall = XsumSmall::new();
+/// assert_eq!(xsmall.add_hex_str("0x1p-1075"), Err(ParseHexError::Inexact));
+/// assert_eq!(xsmall.add_hex_str("1.5"), Err(ParseHexError::Invalid));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseHexError {
+    /// The string is not a hexadecimal floating-point literal, `inf`, `infinity` or `nan`
+    Invalid,
+    /// The value has bits below 2^-1074, the smallest subnormal `f64`
+    Inexact,
+    /// The value is too large for the accumulator to hold exactly
+    OutOfRange,
+}
+
+impl fmt::Display for ParseHexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid => f.write_str("invalid hexadecimal floating-point literal"),
+            Self::Inexact => f.write_str("value has bits below 2^-1074"),
+            Self::OutOfRange => f.write_str("value is too large for the accumulator"),
+        }
+    }
+}
+
+// The provided methods of `Error` are deprecated, unstable, or not meant to be overridden.
+#[allow(clippy::missing_trait_methods)]
+impl Error for ParseHexError {}
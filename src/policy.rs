@@ -0,0 +1,30 @@
+/// How NaN and infinite inputs are handled, chosen when an accumulator is constructed
+///
+/// Values that a policy leaves out of the sum are not counted by `len()` either.
+///
+/// # Example
+///
+/// ```
+/// use xsum::{SpecialValuePolicy, Xsum, XsumSmall};
+///
+/// let mut xsmall = XsumSmall::with_policy(SpecialValuePolicy::IgnoreNaN);
+/// xsmall.add_list(&vec![1.0, f64::NAN, 2.0]);
+/// assert_eq!(xsmall.sum(), 3.0);
+/// assert_eq!(xsmall.len(), 2);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpecialValuePolicy {
+    /// NaN and infinities propagate to `sum()` as in IEEE arithmetic.
+    /// The NaN with the largest payload is kept, and `+Inf` with `-Inf` gives NaN.
+    #[default]
+    Propagate,
+    /// NaN inputs are skipped, like numpy's `nansum`. Infinities propagate.
+    IgnoreNaN,
+    /// NaN and infinite inputs are skipped, so `sum()` is the sum of the finite inputs only.
+    IgnoreNonFinite,
+    /// Like `Propagate`, but the first NaN added is kept instead of the one with the largest payload.
+    FirstNaN,
+    /// NaN and infinite inputs are left out of `sum()`, but are recorded so that
+    /// `try_sum()` returns an error, and `has_nan()`/`has_infinity()` report them.
+    Error,
+}
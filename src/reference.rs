@@ -0,0 +1,300 @@
+//! A slow reference oracle for differential testing
+//!
+//! [`ReferenceSum`] keeps the exact sum as one plain two's complement integer of 2176
+//! bits, in units of the smallest subnormal, and rounds it to `f64` bit by bit. It shares
+//! no code with the accumulators, and is written to be checked by reading rather than to
+//! be fast. [`assert_agrees`] compares `XsumSmall`, `XsumLarge` and `XsumAuto` with it.
+//!
+//! ```
+//! use xsum::reference::{assert_agrees, reference_sum};
+//!
+//! let vec = vec![1e308, 0.1, -1e308, 5e-324];
+//! assert_eq!(reference_sum(&vec), 0.1);
+//! assert_agrees(&vec);
+//! ```
+
+use crate::{Xsum, XsumAuto, XsumLarge, XsumSmall};
+
+// 64-bit words of the integer: 2098 bits hold any finite f64 in units of 2^-1074,
+// and the rest leaves room for carries and the sign
+const REFERENCE_WORDS: usize = 34;
+
+const MANTISSA_BITS: usize = 52;
+const MANTISSA_MASK: u64 = (1 << MANTISSA_BITS) - 1;
+const EXP_MASK: u64 = 0x7ff;
+
+/// ReferenceSum is the exact sum of `f64` values as one big integer
+///
+/// The sum of the finite values is `m_words` times 2^-1074. NaN and infinities are
+/// recorded as flags, and the result follows the same rules as `XsumSmall::sum()` with
+/// the default policy, except that any NaN is returned as `f64::NAN`.
+///
+/// # Example
+///
+/// ```
+/// use xsum::reference::ReferenceSum;
+///
+/// let mut reference = ReferenceSum::new();
+/// reference.add_list(&[1.0, 1e100, 1.0, -1e100]);
+/// assert_eq!(reference.sum(), 2.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReferenceSum {
+    m_words: [u64; REFERENCE_WORDS], // little endian, two's complement
+    m_nan: bool,
+    m_pos_inf: bool,
+    m_neg_inf: bool,
+    m_has_pos_number: bool, // a finite value with the sign bit clear was added
+}
+
+impl Default for ReferenceSum {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReferenceSum {
+    /// Creates an empty sum
+    ///
+    /// ```
+    /// use xsum::reference::ReferenceSum;
+    ///
+    /// assert!(ReferenceSum::new().sum().is_sign_negative());
+    /// ```
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            m_words: [0; REFERENCE_WORDS],
+            m_nan: false,
+            m_pos_inf: false,
+            m_neg_inf: false,
+            m_has_pos_number: false,
+        }
+    }
+
+    /// Adds each value of `vec`
+    ///
+    /// ```
+    /// use xsum::reference::ReferenceSum;
+    ///
+    /// let mut reference = ReferenceSum::new();
+    /// reference.add_list(&[0.1, 0.2, -0.3]);
+    /// assert_eq!(reference.sum(), 2.7755575615628914e-17);
+    /// ```
+    pub fn add_list(&mut self, vec: &[f64]) {
+        for &value in vec {
+            self.add(value);
+        }
+    }
+
+    /// Adds `value`
+    ///
+    /// ```
+    /// use xsum::reference::ReferenceSum;
+    ///
+    /// let mut reference = ReferenceSum::new();
+    /// reference.add(f64::INFINITY);
+    /// reference.add(f64::NEG_INFINITY);
+    /// assert!(reference.sum().is_nan());
+    /// ```
+    pub fn add(&mut self, value: f64) {
+        if value.is_nan() {
+            self.m_nan = true;
+            return;
+        }
+        if value.is_infinite() {
+            if value > 0.0 {
+                self.m_pos_inf = true;
+            } else {
+                self.m_neg_inf = true;
+            }
+            return;
+        }
+        if value.is_sign_positive() {
+            self.m_has_pos_number = true;
+        }
+
+        // value = mantissa * 2^shift * 2^-1074
+        let bits: u64 = value.to_bits();
+        let exp: u64 = (bits >> MANTISSA_BITS) & EXP_MASK;
+        let fraction: u64 = bits & MANTISSA_MASK;
+        let (mantissa, shift): (u64, usize) = if exp == 0 {
+            (fraction, 0)
+        } else {
+            (fraction | (1 << MANTISSA_BITS), exp as usize - 1)
+        };
+
+        let mut operand: [u64; REFERENCE_WORDS] = [0; REFERENCE_WORDS];
+        let word: usize = shift / 64;
+        let offset: usize = shift % 64;
+        operand[word] = mantissa << offset;
+        if offset != 0 {
+            operand[word + 1] = mantissa >> (64 - offset);
+        }
+
+        if value.is_sign_negative() {
+            sub_words(&mut self.m_words, &operand);
+        } else {
+            add_words(&mut self.m_words, &operand);
+        }
+    }
+
+    /// Returns the sum rounded to the nearest `f64`, ties to even
+    ///
+    /// ```
+    /// use xsum::reference::ReferenceSum;
+    ///
+    /// let mut reference = ReferenceSum::new();
+    /// reference.add_list(&[1.0, 2.0_f64.powi(-53)]);
+    /// assert_eq!(reference.sum(), 1.0);
+    /// reference.add(2.0_f64.powi(-80));
+    /// assert_eq!(reference.sum(), 1.0000000000000002);
+    /// ```
+    #[must_use]
+    pub fn sum(&self) -> f64 {
+        if self.m_nan || (self.m_pos_inf && self.m_neg_inf) {
+            return f64::NAN;
+        }
+        if self.m_pos_inf {
+            return f64::INFINITY;
+        }
+        if self.m_neg_inf {
+            return f64::NEG_INFINITY;
+        }
+
+        let negative: bool = self.m_words[REFERENCE_WORDS - 1] >> 63 != 0;
+        let mut magnitude: [u64; REFERENCE_WORDS] = self.m_words;
+        if negative {
+            let mut zero: [u64; REFERENCE_WORDS] = [0; REFERENCE_WORDS];
+            sub_words(&mut zero, &magnitude);
+            magnitude = zero;
+        }
+        let sign: u64 = if negative { 1 << 63 } else { 0 };
+
+        let Some(top) = (0..REFERENCE_WORDS * 64)
+            .rev()
+            .find(|&i| bit(&magnitude, i))
+        else {
+            return if self.m_has_pos_number { 0.0 } else { -0.0 };
+        };
+
+        // Below 2^53 units the value is a subnormal or has the smallest exponent, and its
+        // bits are exactly the integer.
+        if top <= MANTISSA_BITS {
+            return f64::from_bits(sign | magnitude[0]);
+        }
+
+        // Keep the 53 top bits, and round on the bits below them.
+        let mut shift: usize = top - MANTISSA_BITS;
+        let mut mantissa: u64 = 0;
+        for i in shift..=top {
+            mantissa |= u64::from(bit(&magnitude, i)) << (i - shift);
+        }
+        let round: bool = bit(&magnitude, shift - 1);
+        let sticky: bool = (0..shift - 1).any(|i| bit(&magnitude, i));
+        if round && (sticky || mantissa & 1 != 0) {
+            mantissa += 1;
+            if mantissa == 1 << (MANTISSA_BITS + 1) {
+                mantissa >>= 1;
+                shift += 1;
+            }
+        }
+
+        let exp: u64 = shift as u64 + 1;
+        if exp >= EXP_MASK {
+            return f64::from_bits(sign | (EXP_MASK << MANTISSA_BITS));
+        }
+        f64::from_bits(sign | (exp << MANTISSA_BITS) | (mantissa & MANTISSA_MASK))
+    }
+}
+
+// Returns bit i of the integer in `words`
+const fn bit(words: &[u64; REFERENCE_WORDS], i: usize) -> bool {
+    (words[i / 64] >> (i % 64)) & 1 != 0
+}
+
+// Adds `operand` to `words`, wrapping around like two's complement
+fn add_words(words: &mut [u64; REFERENCE_WORDS], operand: &[u64; REFERENCE_WORDS]) {
+    let mut carry: bool = false;
+    for (word, &other) in words.iter_mut().zip(operand) {
+        let (sum, carry1) = word.overflowing_add(other);
+        let (sum, carry2) = sum.overflowing_add(u64::from(carry));
+        *word = sum;
+        carry = carry1 || carry2;
+    }
+}
+
+// Subtracts `operand` from `words`, wrapping around like two's complement
+fn sub_words(words: &mut [u64; REFERENCE_WORDS], operand: &[u64; REFERENCE_WORDS]) {
+    let mut borrow: bool = false;
+    for (word, &other) in words.iter_mut().zip(operand) {
+        let (difference, borrow1) = word.overflowing_sub(other);
+        let (difference, borrow2) = difference.overflowing_sub(u64::from(borrow));
+        *word = difference;
+        borrow = borrow1 || borrow2;
+    }
+}
+
+/// Returns the sum of `vec` from [`ReferenceSum`]
+///
+/// ```
+/// use xsum::reference::reference_sum;
+///
+/// assert_eq!(reference_sum(&[f64::MAX, f64::MAX]), f64::INFINITY);
+/// assert_eq!(reference_sum(&[f64::MAX, f64::MAX, -f64::MAX]), f64::MAX);
+/// ```
+#[must_use]
+pub fn reference_sum(vec: &[f64]) -> f64 {
+    let mut reference: ReferenceSum = ReferenceSum::new();
+    reference.add_list(vec);
+    reference.sum()
+}
+
+/// Asserts that `XsumSmall`, `XsumLarge` and `XsumAuto` give the same sum as
+/// [`ReferenceSum`], both with `add_list()` and with `add()`
+///
+/// Sums are compared bit for bit, except that any NaN matches any NaN.
+///
+/// # Panics
+///
+/// Panics with the name of the accumulator and both sums if one of them disagrees.
+///
+/// ```
+/// use xsum::reference::assert_agrees;
+///
+/// assert_agrees(&[]);
+/// assert_agrees(&[-0.0, 0.0]);
+/// assert_agrees(&[1e300, 1e-300, -1e300, f64::MIN_POSITIVE]);
+/// ```
+pub fn assert_agrees(vec: &[f64]) {
+    let expected: f64 = reference_sum(vec);
+    assert_xsum_agrees::<XsumSmall>("xsumsmall", vec, expected);
+    assert_xsum_agrees::<XsumLarge>("xsumlarge", vec, expected);
+    assert_xsum_agrees::<XsumAuto>("xsumauto", vec, expected);
+}
+
+fn assert_xsum_agrees<X: Xsum>(name: &str, vec: &[f64], expected: f64) {
+    let mut xsum: X = X::new();
+    xsum.add_list(vec);
+    let actual: f64 = xsum.sum();
+    assert!(
+        same_sum(actual, expected),
+        "{name} add_list() sum of {} values is {actual:e}, the reference is {expected:e}",
+        vec.len()
+    );
+
+    let mut xsum: X = X::new();
+    for &value in vec {
+        xsum.add(value);
+    }
+    let actual: f64 = xsum.sum();
+    assert!(
+        same_sum(actual, expected),
+        "{name} add() sum of {} values is {actual:e}, the reference is {expected:e}",
+        vec.len()
+    );
+}
+
+fn same_sum(actual: f64, expected: f64) -> bool {
+    actual.to_bits() == expected.to_bits() || (actual.is_nan() && expected.is_nan())
+}
@@ -0,0 +1,27 @@
+/// How an exact value is rounded to an integer, or to decimal places, chosen for each call of
+/// `round_i128()`, `to_decimal_rounded()` or `to_decimal_string()`
+///
+/// # Example
+///
+/// ```
+/// use xsum::{RoundingMode, Xsum, XsumSmall};
+///
+/// let mut xsmall = XsumSmall::new();
+/// xsmall.add_list(&vec![2.0, 0.5]);
+/// assert_eq!(xsmall.round_i128(RoundingMode::HalfEven), Ok(2));
+/// assert_eq!(xsmall.round_i128(RoundingMode::HalfUp), Ok(3));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RoundingMode {
+    /// To nearest, ties to even, as `sum()` rounds to `f64`
+    #[default]
+    HalfEven,
+    /// To nearest, ties away from zero, as in the usual "round half up" of decimal amounts
+    HalfUp,
+    /// Toward zero, dropping the fractional part
+    TowardZero,
+    /// Toward negative infinity
+    Floor,
+    /// Toward positive infinity
+    Ceil,
+}
@@ -0,0 +1,29 @@
+use std::fmt;
+
+/// Sign of an infinity, of an overflowed sum or of an exact value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sign {
+    Positive,
+    Negative,
+}
+
+impl Sign {
+    /// Returns the sign of the bits of a `f64`
+    #[inline(always)]
+    pub(crate) const fn of_bits(bits: i64) -> Self {
+        if bits < 0 {
+            Self::Negative
+        } else {
+            Self::Positive
+        }
+    }
+}
+
+impl fmt::Display for Sign {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Positive => f.write_str("+"),
+            Self::Negative => f.write_str("-"),
+        }
+    }
+}
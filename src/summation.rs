@@ -0,0 +1,362 @@
+//! A common interface to the exact accumulators and to classic inexact summation algorithms
+//!
+//! [`Summation`] is implemented for every [`Xsum`] accumulator, and for [`NaiveSum`],
+//! [`PairwiseSum`], [`KahanSum`] and [`NeumaierSum`], so that they can be swapped through
+//! generics to compare their accuracy and speed. Its methods have the same names as those
+//! of `Xsum`, so this module is not re-exported at the crate root, and `use xsum::*` does
+//! not bring `Summation` into scope.
+//!
+//! ```
+//! use xsum::summation::{KahanSum, NaiveSum, NeumaierSum, PairwiseSum, Summation};
+//! use xsum::XsumAuto;
+//!
+//! fn sum_with<S: Summation>(vec: &[f64]) -> f64 {
+//!     let mut summation = S::new();
+//!     summation.add_list(vec);
+//!     summation.sum()
+//! }
+//!
+//! let vec = vec![1.0, 1e100, 1.0, -1e100];
+//! assert_eq!(sum_with::<NaiveSum>(&vec), 0.0);
+//! assert_eq!(sum_with::<PairwiseSum>(&vec), 0.0);
+//! assert_eq!(sum_with::<KahanSum>(&vec), 0.0);
+//! assert_eq!(sum_with::<NeumaierSum>(&vec), 2.0);
+//! assert_eq!(sum_with::<XsumAuto>(&vec), 2.0);
+//! ```
+
+use std::any::type_name;
+
+use crate::traits::Xsum;
+
+// Number of values summed in a loop before a partial sum joins the pairwise tree
+const PAIRWISE_BLOCK_SIZE: usize = 128;
+
+/// Summation is a common interface to the exact accumulators and to classic inexact
+/// summation algorithms
+///
+/// If both `Summation` and `Xsum` are in scope, their methods on an `Xsum` accumulator
+/// have to be called through the trait, as in `Summation::sum(&mut xsmall)`.
+///
+/// # Example
+///
+/// ```
+/// use xsum::summation::{NaiveSum, Summation};
+///
+/// assert_eq!(NaiveSum::name(), "NaiveSum");
+/// assert_eq!(<xsum::XsumAuto as Summation>::name(), "XsumAuto");
+/// ```
+pub trait Summation {
+    /// Returns the name of the type, for reports and benchmarks
+    fn name() -> &'static str;
+    fn new() -> Self;
+    fn add_list(&mut self, vec: &[f64]);
+    fn add(&mut self, value: f64);
+    fn sum(&mut self) -> f64;
+    fn clear(&mut self);
+}
+
+// The name of `T` without its module path
+fn short_type_name<T>() -> &'static str {
+    let path = type_name::<T>();
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+impl<X: Xsum> Summation for X {
+    fn name() -> &'static str {
+        short_type_name::<Self>()
+    }
+
+    fn new() -> Self {
+        <Self as Xsum>::new()
+    }
+
+    fn add_list(&mut self, vec: &[f64]) {
+        Xsum::add_list(self, vec);
+    }
+
+    fn add(&mut self, value: f64) {
+        Xsum::add(self, value);
+    }
+
+    fn sum(&mut self) -> f64 {
+        Xsum::sum(self)
+    }
+
+    fn clear(&mut self) {
+        Xsum::clear(self);
+    }
+}
+
+/// NaiveSum adds the values one after another in `f64`, as a plain loop does
+///
+/// Each addition may round, so the error can grow with the number of values and with
+/// cancellation.
+///
+/// # Example
+///
+/// ```
+/// use xsum::summation::{NaiveSum, Summation};
+///
+/// let mut naive = NaiveSum::new();
+/// naive.add_list(&vec![0.1; 10]);
+/// assert_eq!(naive.sum(), 0.9999999999999999);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NaiveSum {
+    m_sum: f64,
+}
+
+impl Default for NaiveSum {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Summation for NaiveSum {
+    fn name() -> &'static str {
+        short_type_name::<Self>()
+    }
+
+    fn new() -> Self {
+        // -0.0 is the sum of no values, and leaves the sign of any added zero unchanged
+        Self { m_sum: -0.0 }
+    }
+
+    fn add_list(&mut self, vec: &[f64]) {
+        for &value in vec {
+            self.m_sum += value;
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        self.m_sum += value;
+    }
+
+    fn sum(&mut self) -> f64 {
+        self.m_sum
+    }
+
+    fn clear(&mut self) {
+        self.m_sum = -0.0;
+    }
+}
+
+/// PairwiseSum adds blocks of values in a loop, then adds the block sums pairwise
+///
+/// The error grows with the logarithm of the number of values rather than with the number
+/// itself, at about the speed of a plain loop. Values can be added one at a time: the
+/// block sums are combined as a binary counter, giving the same result as `add_list()`.
+///
+/// # Example
+///
+/// ```
+/// use xsum::summation::{NaiveSum, PairwiseSum, Summation};
+///
+/// let vec = vec![0.1; 10_000];
+/// let mut naive = NaiveSum::new();
+/// naive.add_list(&vec);
+/// assert_eq!(naive.sum(), 1000.0000000001588);
+///
+/// let mut pairwise = PairwiseSum::new();
+/// pairwise.add_list(&vec);
+/// assert_eq!(pairwise.sum(), 999.9999999999977);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PairwiseSum {
+    m_block_sum: f64,
+    m_block_len: usize,
+    m_partials: Vec<(f64, u32)>, // sums of 2^level blocks, with the largest level first
+}
+
+impl Default for PairwiseSum {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Summation for PairwiseSum {
+    fn name() -> &'static str {
+        short_type_name::<Self>()
+    }
+
+    fn new() -> Self {
+        Self {
+            m_block_sum: -0.0,
+            m_block_len: 0,
+            m_partials: Vec::new(),
+        }
+    }
+
+    fn add_list(&mut self, vec: &[f64]) {
+        for &value in vec {
+            self.add(value);
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        self.m_block_sum += value;
+        self.m_block_len += 1;
+        if self.m_block_len < PAIRWISE_BLOCK_SIZE {
+            return;
+        }
+
+        // Merge equal levels, like carries of a binary counter.
+        let mut partial: (f64, u32) = (self.m_block_sum, 0);
+        while let Some(&(sum, level)) = self.m_partials.last() {
+            if level != partial.1 {
+                break;
+            }
+            self.m_partials.pop();
+            partial = (sum + partial.0, level + 1);
+        }
+        self.m_partials.push(partial);
+        self.m_block_sum = -0.0;
+        self.m_block_len = 0;
+    }
+
+    fn sum(&mut self) -> f64 {
+        // from the smallest partial sum to the largest
+        self.m_partials
+            .iter()
+            .rev()
+            .fold(self.m_block_sum, |total, &(sum, _)| sum + total)
+    }
+
+    fn clear(&mut self) {
+        self.m_block_sum = -0.0;
+        self.m_block_len = 0;
+        self.m_partials.clear();
+    }
+}
+
+/// KahanSum carries the rounding error of each addition into the next one
+///
+/// The error no longer grows with the number of values, but cancellation between large
+/// values still loses the small ones. As in the textbook algorithm, any value added after
+/// an infinite one makes the sum NaN.
+///
+/// # Example
+///
+/// ```
+/// use xsum::summation::{KahanSum, Summation};
+///
+/// let mut kahan = KahanSum::new();
+/// kahan.add_list(&vec![0.1; 10]);
+/// assert_eq!(kahan.sum(), 1.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct KahanSum {
+    m_sum: f64,
+    m_compensation: f64,
+}
+
+impl Default for KahanSum {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Summation for KahanSum {
+    fn name() -> &'static str {
+        short_type_name::<Self>()
+    }
+
+    fn new() -> Self {
+        Self {
+            m_sum: -0.0,
+            m_compensation: 0.0,
+        }
+    }
+
+    fn add_list(&mut self, vec: &[f64]) {
+        for &value in vec {
+            self.add(value);
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        let y: f64 = value - self.m_compensation;
+        let t: f64 = self.m_sum + y;
+        self.m_compensation = (t - self.m_sum) - y;
+        self.m_sum = t;
+    }
+
+    fn sum(&mut self) -> f64 {
+        self.m_sum
+    }
+
+    fn clear(&mut self) {
+        self.m_sum = -0.0;
+        self.m_compensation = 0.0;
+    }
+}
+
+/// NeumaierSum keeps the rounding errors of the additions in a separate sum, added at the end
+///
+/// Unlike `KahanSum`, this also keeps the error when a value is larger than the running
+/// sum, so small values survive cancellation between large ones, as long as the errors
+/// themselves add up exactly enough. A NaN or an infinite sum is returned as it is.
+///
+/// # Example
+///
+/// ```
+/// use xsum::summation::{NeumaierSum, Summation};
+///
+/// let mut neumaier = NeumaierSum::new();
+/// neumaier.add_list(&vec![1.0, 1e100, 1.0, -1e100]);
+/// assert_eq!(neumaier.sum(), 2.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NeumaierSum {
+    m_sum: f64,
+    m_compensation: f64,
+}
+
+impl Default for NeumaierSum {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Summation for NeumaierSum {
+    fn name() -> &'static str {
+        short_type_name::<Self>()
+    }
+
+    fn new() -> Self {
+        Self {
+            m_sum: -0.0,
+            m_compensation: 0.0,
+        }
+    }
+
+    fn add_list(&mut self, vec: &[f64]) {
+        for &value in vec {
+            self.add(value);
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        let t: f64 = self.m_sum + value;
+        if self.m_sum.abs() >= value.abs() {
+            self.m_compensation += (self.m_sum - t) + value;
+        } else {
+            self.m_compensation += (value - t) + self.m_sum;
+        }
+        self.m_sum = t;
+    }
+
+    fn sum(&mut self) -> f64 {
+        // The compensation of an infinite sum is NaN, and adding a zero one could
+        // change the sign of a zero sum.
+        if !self.m_sum.is_finite() || self.m_compensation == 0.0 {
+            return self.m_sum;
+        }
+        self.m_sum + self.m_compensation
+    }
+
+    fn clear(&mut self) {
+        self.m_sum = -0.0;
+        self.m_compensation = 0.0;
+    }
+}
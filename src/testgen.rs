@@ -0,0 +1,359 @@
+//! Generators of hard test data with known exact sums
+//!
+//! [`TestGen`] is a small seedable generator, so that a failing data set can be reproduced
+//! from its seed. Each generator returns [`TestData`]: the values, and their sum correctly
+//! rounded by [`reference_sum`](crate::reference::reference_sum), which does not share
+//! code with the accumulators.
+//!
+//! ```
+//! use xsum::testgen::TestGen;
+//!
+//! let mut testgen = TestGen::new(42);
+//! let data = testgen.ill_conditioned(1_000, 1e20);
+//! let naive: f64 = data.values().iter().sum();
+//! assert_ne!(naive, data.expected());
+//! ```
+
+use crate::{reference::reference_sum, Xsum, XsumProfile, XsumSmall};
+
+const MANTISSA_BITS: i32 = 52;
+const MANTISSA_MASK: u64 = (1 << MANTISSA_BITS) - 1;
+const EXP_BIAS: i32 = 1023;
+const MIN_NORMAL_EXP: i32 = 1 - EXP_BIAS;
+const MIN_SUBNORMAL_EXP: i32 = MIN_NORMAL_EXP - MANTISSA_BITS;
+const SIGN_MASK: u64 = 1 << 63;
+
+/// TestData is a generated vector with its correctly rounded sum
+///
+/// # Example
+///
+/// ```
+/// use xsum::{testgen::TestGen, Xsum, XsumAuto};
+///
+/// let data = TestGen::new(7).cancellation(100);
+/// let mut xauto = XsumAuto::new();
+/// xauto.add_list(data.values());
+/// assert_eq!(xauto.sum(), data.expected());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TestData {
+    m_values: Vec<f64>,
+    m_expected: f64,
+}
+
+impl TestData {
+    fn new(values: Vec<f64>) -> Self {
+        let m_expected: f64 = reference_sum(&values);
+        Self {
+            m_values: values,
+            m_expected,
+        }
+    }
+
+    /// Returns the generated values
+    ///
+    /// ```
+    /// use xsum::testgen::TestGen;
+    ///
+    /// assert_eq!(TestGen::new(1).subnormals(10).values().len(), 10);
+    /// ```
+    #[must_use]
+    pub fn values(&self) -> &[f64] {
+        &self.m_values
+    }
+
+    /// Returns the exact sum of the values, rounded once to the nearest `f64`
+    ///
+    /// ```
+    /// use xsum::testgen::TestGen;
+    ///
+    /// assert!(TestGen::new(1).subnormals(10).expected().abs() < f64::MIN_POSITIVE * 10.0);
+    /// ```
+    #[must_use]
+    pub const fn expected(&self) -> f64 {
+        self.m_expected
+    }
+
+    /// Returns the condition number of the sum, Σ|x| / |Σx|.
+    /// See [`XsumProfile::condition_number`].
+    ///
+    /// ```
+    /// use xsum::testgen::TestGen;
+    ///
+    /// let condition: f64 = TestGen::new(1).ill_conditioned(100, 1e30).condition_number();
+    /// assert!(condition > 1e25 && condition < 1e35);
+    /// ```
+    #[must_use]
+    pub fn condition_number(&self) -> f64 {
+        let mut xprofile: XsumProfile = XsumProfile::new();
+        xprofile.add_list(&self.m_values);
+        xprofile.condition_number()
+    }
+
+    /// Returns the values, giving up the expected sum
+    ///
+    /// ```
+    /// use xsum::testgen::TestGen;
+    ///
+    /// let values: Vec<f64> = TestGen::new(1).exponent_spread(10).into_values();
+    /// assert_eq!(values.len(), 10);
+    /// ```
+    #[must_use]
+    pub fn into_values(self) -> Vec<f64> {
+        self.m_values
+    }
+}
+
+/// TestGen generates hard test data from a seed
+///
+/// The generator is SplitMix64: the same seed gives the same data on every platform and
+/// release of this crate that does not change the generators.
+///
+/// # Example
+///
+/// ```
+/// use xsum::testgen::TestGen;
+///
+/// let a = TestGen::new(3).exponent_spread(50);
+/// let b = TestGen::new(3).exponent_spread(50);
+/// assert_eq!(a.values(), b.values());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TestGen {
+    m_state: u64,
+}
+
+impl TestGen {
+    /// Creates a generator from `seed`
+    ///
+    /// ```
+    /// use xsum::testgen::TestGen;
+    ///
+    /// let mut testgen = TestGen::new(0);
+    /// assert_ne!(testgen.next_u64(), testgen.next_u64());
+    /// ```
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self { m_state: seed }
+    }
+
+    /// Returns the next 64 random bits
+    ///
+    /// ```
+    /// use xsum::testgen::TestGen;
+    ///
+    /// assert_eq!(TestGen::new(1234567).next_u64(), 6457827717110365317);
+    /// ```
+    pub fn next_u64(&mut self) -> u64 {
+        self.m_state = self.m_state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z: u64 = self.m_state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a random number below `bound`, or 0 if `bound` is 0
+    ///
+    /// ```
+    /// use xsum::testgen::TestGen;
+    ///
+    /// let mut testgen = TestGen::new(1);
+    /// assert!((0..100).all(|_| testgen.below(6) < 6));
+    /// ```
+    pub fn below(&mut self, bound: u64) -> u64 {
+        ((u128::from(self.next_u64()) * u128::from(bound)) >> 64) as u64
+    }
+
+    /// Returns values with an exact sum of condition number about `condition`, as in
+    /// the GenSum algorithm of Ogita, Rump and Oishi
+    ///
+    /// The first half of the values have random exponents up to log2(`condition`). The
+    /// exponents of the second half fall to zero, each value cancelling most of the sum
+    /// before it, so that the sum is about 1 and the sum of magnitudes about `condition`,
+    /// when `condition` is well above `len`. The values are then shuffled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is below 6, or if `condition` is not between 1 and 1e300.
+    ///
+    /// ```
+    /// use xsum::testgen::TestGen;
+    ///
+    /// let data = TestGen::new(5).ill_conditioned(1_000, 1e100);
+    /// assert_eq!(data.values().len(), 1_000);
+    /// assert!(data.condition_number() > 1e95);
+    /// ```
+    pub fn ill_conditioned(&mut self, len: usize, condition: f64) -> TestData {
+        assert!(len >= 6, "ill_conditioned() needs at least 6 values");
+        assert!(
+            (1.0..=1e300).contains(&condition),
+            "ill_conditioned() needs a condition between 1 and 1e300"
+        );
+        let top_exp: f64 = condition.log2();
+        let first: usize = len / 2;
+        let rest: usize = len - first;
+
+        let mut values: Vec<f64> = Vec::with_capacity(len);
+        for i in 0..first {
+            let exp: i32 = if i == 0 {
+                top_exp.round() as i32 + 1
+            } else if i == first - 1 {
+                0
+            } else {
+                (self.unit() * top_exp).round() as i32
+            };
+            values.push(self.random_value(exp));
+        }
+
+        let mut xsmall: XsumSmall = XsumSmall::new();
+        xsmall.add_list(&values);
+        for i in 0..rest {
+            let fraction: f64 = i as f64 / (rest - 1) as f64;
+            let exp: i32 = (top_exp * (1.0 - fraction)).round() as i32;
+            let value: f64 = self.random_value(exp) - xsmall.sum();
+            values.push(value);
+            xsmall.add(value);
+        }
+
+        self.shuffle(&mut values);
+        TestData::new(values)
+    }
+
+    /// Returns values with exponents spread over the whole range of `f64`, from the
+    /// smallest subnormals to just below the largest values, with random signs
+    ///
+    /// ```
+    /// use xsum::testgen::TestGen;
+    ///
+    /// let data = TestGen::new(9).exponent_spread(1_000);
+    /// assert!(data.values().iter().all(|value| value.is_finite()));
+    /// ```
+    pub fn exponent_spread(&mut self, len: usize) -> TestData {
+        let span: u64 = (EXP_BIAS - MIN_SUBNORMAL_EXP) as u64;
+        let values: Vec<f64> = (0..len)
+            .map(|_| {
+                let exp: i32 = MIN_SUBNORMAL_EXP + self.below(span) as i32;
+                self.random_value(exp)
+            })
+            .collect();
+        TestData::new(values)
+    }
+
+    /// Returns pairs of large values that cancel exactly, shuffled with a few small values
+    /// that make up the whole sum
+    ///
+    /// About one value in sixteen is small, and at least one is.
+    ///
+    /// ```
+    /// use xsum::testgen::TestGen;
+    ///
+    /// let data = TestGen::new(2).cancellation(1_000);
+    /// let naive: f64 = data.values().iter().sum();
+    /// assert!(data.expected().abs() < 1e-30);
+    /// assert!(naive.abs() > 1.0);
+    /// ```
+    pub fn cancellation(&mut self, len: usize) -> TestData {
+        let small_len: usize = (len / 16).max(1).min(len);
+        let pairs: usize = (len - small_len) / 2;
+        let mut values: Vec<f64> = Vec::with_capacity(len);
+        for _ in 0..pairs {
+            let exp: i32 = self.below(200) as i32;
+            let value: f64 = self.random_value(exp);
+            values.push(value);
+            values.push(-value);
+        }
+        while values.len() < len {
+            let exp: i32 = -200 + self.below(100) as i32;
+            values.push(self.random_value(exp));
+        }
+        self.shuffle(&mut values);
+        TestData::new(values)
+    }
+
+    /// Returns subnormal values, and a quarter of normal ones with the two smallest
+    /// exponents, with random signs
+    ///
+    /// ```
+    /// use xsum::testgen::TestGen;
+    ///
+    /// let data = TestGen::new(4).subnormals(100);
+    /// assert!(data.values().iter().any(|value| value.is_subnormal()));
+    /// ```
+    pub fn subnormals(&mut self, len: usize) -> TestData {
+        let values: Vec<f64> = (0..len)
+            .map(|_| {
+                let exp: i32 = if self.below(4) == 0 {
+                    MIN_NORMAL_EXP + self.below(2) as i32
+                } else {
+                    MIN_SUBNORMAL_EXP + self.below(MANTISSA_BITS as u64) as i32
+                };
+                self.random_value(exp)
+            })
+            .collect();
+        TestData::new(values)
+    }
+
+    /// Returns finite values mixed with infinities and NaN
+    ///
+    /// About one value in eight is `+inf`, `-inf` or NaN, and at least one is. The expected
+    /// sum is NaN if there is a NaN or both infinities, as with the default policy.
+    ///
+    /// ```
+    /// use xsum::testgen::TestGen;
+    ///
+    /// let data = TestGen::new(6).special_values(100);
+    /// assert!(!data.expected().is_finite());
+    /// ```
+    pub fn special_values(&mut self, len: usize) -> TestData {
+        let mut values: Vec<f64> = (0..len)
+            .map(|_| {
+                if self.below(8) == 0 {
+                    self.special_value()
+                } else {
+                    let exp: i32 = -100 + self.below(200) as i32;
+                    self.random_value(exp)
+                }
+            })
+            .collect();
+        if !values.iter().any(|value| !value.is_finite()) && len > 0 {
+            let index: usize = self.below(len as u64) as usize;
+            values[index] = self.special_value();
+        }
+        TestData::new(values)
+    }
+
+    // Returns a random number in [0, 1)
+    fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Returns a value with a random sign and mantissa, in [2^exp, 2^(exp+1)) in magnitude,
+    // or a random subnormal below 2^(exp+1) if `exp` is below the normal range
+    fn random_value(&mut self, exp: i32) -> f64 {
+        let bits: u64 = self.next_u64();
+        let sign: u64 = bits & SIGN_MASK;
+        let fraction: u64 = bits & MANTISSA_MASK;
+        if exp < MIN_NORMAL_EXP {
+            let mantissa: u64 = (fraction | (1 << MANTISSA_BITS)) >> (MIN_NORMAL_EXP - exp);
+            return f64::from_bits(sign | mantissa);
+        }
+        let field: u64 = (exp + EXP_BIAS) as u64;
+        f64::from_bits(sign | (field << MANTISSA_BITS) | fraction)
+    }
+
+    fn special_value(&mut self) -> f64 {
+        match self.below(3) {
+            0 => f64::INFINITY,
+            1 => f64::NEG_INFINITY,
+            _ => f64::NAN,
+        }
+    }
+
+    // Fisher-Yates shuffle
+    fn shuffle(&mut self, values: &mut [f64]) {
+        for i in (1..values.len()).rev() {
+            let j: usize = self.below(i as u64 + 1) as usize;
+            values.swap(i, j);
+        }
+    }
+}
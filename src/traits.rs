@@ -1,4 +1,9 @@
-use crate::{constants::XSUM_THRESHOLD, xsum_large, xsum_small};
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+};
+
+use crate::{constants::XSUM_THRESHOLD, exact_f64, xsum_auto, xsum_large, xsum_small};
 
 /// Xsum trait
 ///
@@ -42,20 +47,157 @@ pub trait Xsum {
 /// let vec = vec![1.0, 2.0, 3.0];
 /// assert_eq!(vec.xsum(), 6.0);
 /// ```
+///
+/// Slices of other `ExactAddend` types are summed the same way.
+///
+/// ```
+/// use xsum::XsumExt;
+///
+/// assert_eq!([0.1f32; 10].xsum(), 1.0000000149011612);
+/// assert_eq!([i32::MAX; 3].xsum(), 6_442_450_941.0);
+/// ```
 pub trait XsumExt {
     fn xsum(&self) -> f64;
 }
 
-impl XsumExt for [f64] {
+impl<T: ExactAddend> XsumExt for [T] {
     fn xsum(&self) -> f64 {
         if self.len() < XSUM_THRESHOLD {
             let mut xsumsmall = xsum_small::XsumSmall::new();
-            xsumsmall.add_list(self);
+            xsumsmall.add_slice(self);
             xsumsmall.sum()
         } else {
             let mut xsumlarge = xsum_large::XsumLarge::new();
-            xsumlarge.add_list(self);
+            xsumlarge.add_slice(self);
             xsumlarge.sum()
         }
     }
 }
+
+/// Primitive numbers that convert to `f64` without rounding
+///
+/// These can be added with `add_generic()` and `add_slice()`, and summed with `XsumExt`,
+/// without a cast at each call. Wider integers, which `f64` may not hold exactly, have their
+/// own methods, such as `add_i64()`. With the `half` feature, `half::f16` and `half::bf16`
+/// are addends too.
+///
+/// # Example
+///
+/// ```
+/// use xsum::{Xsum, XsumSmall};
+///
+/// let mut xsmall = XsumSmall::new();
+/// xsmall.add_generic(0.5f32);
+/// xsmall.add_generic(-3i8);
+/// xsmall.add_slice(&[u32::MAX, 1]);
+/// assert_eq!(xsmall.sum(), 4_294_967_293.5);
+/// ```
+pub trait ExactAddend: Copy {
+    /// Returns the value as an `f64`, which must be exactly equal to it
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_exact_addend {
+    ($($t:ty),*) => {
+        $(
+            impl ExactAddend for $t {
+                #[inline(always)]
+                fn to_f64(self) -> f64 {
+                    f64::from(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_exact_addend!(f64, f32, i8, i16, i32, u8, u16, u32);
+
+#[cfg(feature = "half")]
+impl_exact_addend!(half::f16, half::bf16);
+
+// Accumulators and `ExactF64` compare and hash by their exact value, so that equal sums are equal keys
+// whatever the order of their inputs. All NaNs are equal and greater than `+Inf`.
+macro_rules! impl_exact_order {
+    ($($t:ty),*) => {
+        $(
+            // `ne` is left to the default, and `Eq` and `PartialOrd` have hidden unstable
+            // methods that cannot be implemented on stable Rust.
+            #[allow(clippy::missing_trait_methods)]
+            impl PartialEq for $t {
+                fn eq(&self, other: &Self) -> bool {
+                    self.cmp(other).is_eq()
+                }
+            }
+
+            #[allow(clippy::missing_trait_methods)]
+            impl Eq for $t {}
+
+            #[allow(clippy::missing_trait_methods)]
+            impl PartialOrd for $t {
+                fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                    Some(self.cmp(other))
+                }
+
+                fn lt(&self, other: &Self) -> bool {
+                    self.cmp(other).is_lt()
+                }
+
+                fn le(&self, other: &Self) -> bool {
+                    self.cmp(other).is_le()
+                }
+
+                fn gt(&self, other: &Self) -> bool {
+                    self.cmp(other).is_gt()
+                }
+
+                fn ge(&self, other: &Self) -> bool {
+                    self.cmp(other).is_ge()
+                }
+            }
+
+            impl Ord for $t {
+                fn cmp(&self, other: &Self) -> Ordering {
+                    self.with_exact(|sacc| other.with_exact(|other_sacc| sacc.total_cmp(other_sacc)))
+                }
+
+                fn max(self, other: Self) -> Self {
+                    std::cmp::max_by(self, other, Ord::cmp)
+                }
+
+                fn min(self, other: Self) -> Self {
+                    std::cmp::min_by(self, other, Ord::cmp)
+                }
+
+                fn clamp(self, min: Self, max: Self) -> Self {
+                    assert!(min <= max, "clamp() needs min <= max");
+                    if self < min {
+                        min
+                    } else if self > max {
+                        max
+                    } else {
+                        self
+                    }
+                }
+            }
+
+            impl Hash for $t {
+                fn hash<H: Hasher>(&self, state: &mut H) {
+                    self.with_exact(|sacc| sacc.hash_value(state));
+                }
+
+                fn hash_slice<H: Hasher>(data: &[Self], state: &mut H) {
+                    for accumulator in data {
+                        accumulator.hash(state);
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_exact_order!(
+    xsum_small::XsumSmall,
+    xsum_large::XsumLarge,
+    xsum_auto::XsumAuto,
+    exact_f64::ExactF64
+);
@@ -1,4 +1,17 @@
-use crate::{constants::XSUM_THRESHOLD, traits::Xsum, XsumLarge, XsumSmall};
+use std::cmp::Ordering;
+
+use crate::{
+    accumulators::small_accumulator::SmallAccumulator,
+    constants::XSUM_THRESHOLD,
+    error::XsumError,
+    flags::SumFlags,
+    parse_hex_error::ParseHexError,
+    policy::SpecialValuePolicy,
+    rounding_mode::RoundingMode,
+    sign::Sign,
+    traits::{ExactAddend, Xsum},
+    XsumLarge, XsumSmall,
+};
 
 #[cfg_attr(debug_assertions, derive(Debug))]
 enum XsumKind {
@@ -36,10 +49,41 @@ impl Default for XsumAuto {
 }
 
 impl XsumAuto {
+    /// Creates an accumulator that handles NaN and infinite inputs according to `policy`
+    ///
+    /// ```
+    /// use xsum::{SpecialValuePolicy, Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::with_policy(SpecialValuePolicy::IgnoreNaN);
+    /// xauto.add_list(&vec![1.0, f64::NAN, 2.0]);
+    /// assert_eq!(xauto.sum(), 3.0);
+    /// ```
+    #[must_use]
+    pub fn with_policy(policy: SpecialValuePolicy) -> Self {
+        Self {
+            m_xsum: XsumKind::XSmall(XsumSmall::with_policy(policy)),
+        }
+    }
+
+    /// Returns the policy for NaN and infinite inputs
+    ///
+    /// ```
+    /// use xsum::{SpecialValuePolicy, Xsum, XsumAuto};
+    ///
+    /// assert_eq!(XsumAuto::new().policy(), SpecialValuePolicy::Propagate);
+    /// ```
+    #[must_use]
+    pub const fn policy(&self) -> SpecialValuePolicy {
+        match &self.m_xsum {
+            XsumKind::XSmall(xsmall) => xsmall.policy(),
+            XsumKind::XLarge(xlarge) => xlarge.policy(),
+        }
+    }
+
     #[inline(always)]
     fn transform_to_large(&mut self) {
         let should_transform = match &self.m_xsum {
-            XsumKind::XSmall(xsmall) => xsmall.get_size_count() > XSUM_THRESHOLD,
+            XsumKind::XSmall(xsmall) => xsmall.len() > XSUM_THRESHOLD,
             XsumKind::XLarge(_) => false,
         };
         if !should_transform {
@@ -53,6 +97,902 @@ impl XsumAuto {
             other @ XsumKind::XLarge(_) => other,
         };
     }
+
+    /// Calls `f` with the small accumulator, or with a copy holding the value of the large one
+    fn with_small<R>(&mut self, f: impl FnOnce(&mut XsumSmall) -> R) -> R {
+        match &mut self.m_xsum {
+            XsumKind::XSmall(xsmall) => f(xsmall),
+            XsumKind::XLarge(xlarge) => f(&mut xlarge.transfer_and_copy_small()),
+        }
+    }
+
+    /// Returns the number of values added so far
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![1.0; 2_000]);
+    /// assert_eq!(xauto.len(), 2_000);
+    /// ```
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        match &self.m_xsum {
+            XsumKind::XSmall(xsmall) => xsmall.len(),
+            XsumKind::XLarge(xlarge) => xlarge.len(),
+        }
+    }
+
+    /// Returns `true` if no value has been added
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// assert!(xauto.is_empty());
+    /// xauto.add(0.0);
+    /// assert!(!xauto.is_empty());
+    /// ```
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        match &self.m_xsum {
+            XsumKind::XSmall(xsmall) => xsmall.is_empty(),
+            XsumKind::XLarge(xlarge) => xlarge.is_empty(),
+        }
+    }
+
+    /// Returns `true` if a NaN has been added. See [`XsumSmall::has_nan`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.0; 2_000]); // switch to XsumLarge
+    /// xauto.add_list(&vec![1.0, f64::NAN]);
+    /// assert!(xauto.has_nan());
+    /// ```
+    #[must_use]
+    pub const fn has_nan(&self) -> bool {
+        match &self.m_xsum {
+            XsumKind::XSmall(xsmall) => xsmall.has_nan(),
+            XsumKind::XLarge(xlarge) => xlarge.has_nan(),
+        }
+    }
+
+    /// Returns `true` if `+Inf` or `-Inf` has been added
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![1.0, f64::NEG_INFINITY]);
+    /// assert!(xauto.has_infinity());
+    /// ```
+    #[must_use]
+    pub const fn has_infinity(&self) -> bool {
+        match &self.m_xsum {
+            XsumKind::XSmall(xsmall) => xsmall.has_infinity(),
+            XsumKind::XLarge(xlarge) => xlarge.has_infinity(),
+        }
+    }
+
+    /// Returns the 52-bit mantissa field of the NaN that `sum()` would return,
+    /// or `None` if no NaN has been added. See [`XsumSmall::nan_payload`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.0; 2_000]); // switch to XsumLarge
+    /// xauto.add(f64::from_bits(0x7ff8_0000_0000_002a));
+    /// assert_eq!(xauto.nan_payload(), Some(0x8_0000_0000_002a));
+    /// ```
+    #[must_use]
+    pub const fn nan_payload(&self) -> Option<u64> {
+        match &self.m_xsum {
+            XsumKind::XSmall(xsmall) => xsmall.nan_payload(),
+            XsumKind::XLarge(xlarge) => xlarge.nan_payload(),
+        }
+    }
+
+    /// Returns a human-readable listing of the chunks of the underlying accumulator.
+    /// See [`XsumSmall::debug_dump`] and [`XsumLarge::debug_dump`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add(1.0);
+    /// assert!(xauto.debug_dump().starts_with("small accumulator"));
+    /// ```
+    #[must_use]
+    pub fn debug_dump(&self) -> String {
+        match &self.m_xsum {
+            XsumKind::XSmall(xsmall) => xsmall.debug_dump(),
+            XsumKind::XLarge(xlarge) => xlarge.debug_dump(),
+        }
+    }
+
+    /// Returns the same value as `sum()` if it is finite, otherwise the reason why it is not.
+    /// See [`XsumSmall::try_sum`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto, XsumError};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![1.0; 10]);
+    /// assert_eq!(xauto.try_sum(), Ok(10.0));
+    ///
+    /// xauto.add(f64::NAN);
+    /// assert!(matches!(xauto.try_sum(), Err(XsumError::NaNInput { .. })));
+    /// ```
+    pub fn try_sum(&mut self) -> Result<f64, XsumError> {
+        self.with_small(|xsmall| xsmall.try_sum())
+    }
+
+    /// Returns the same value as `sum()`, together with flags telling whether it had to be rounded.
+    /// See [`XsumSmall::sum_with_flags`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.5; 10]);
+    /// let (sum, flags) = xauto.sum_with_flags();
+    /// assert_eq!(sum, 5.0);
+    /// assert!(!flags.inexact);
+    /// ```
+    pub fn sum_with_flags(&mut self) -> (f64, SumFlags) {
+        self.with_small(|xsmall| xsmall.sum_with_flags())
+    }
+
+    /// Returns the exact sum rounded to 53 significant bits as `(mantissa, exponent)`,
+    /// with an exponent that is not limited to the range of `f64`.
+    /// See [`XsumSmall::sum_extended`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![3.0; 10]);
+    /// assert_eq!(xauto.sum_extended(), (0.9375, 5));
+    /// ```
+    pub fn sum_extended(&mut self) -> (f64, i64) {
+        self.with_small(|xsmall| xsmall.sum_extended())
+    }
+
+    /// Returns the exact sum rounded once to a narrower binary format.
+    /// See [`XsumSmall::sum_to_precision`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mantissa_bits` is more than 52, or if the exponents are not in order
+    /// within -1022 to 1023.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.1; 10]);
+    /// assert_eq!(xauto.sum_to_precision(1, -1, 1), 1.0);
+    /// ```
+    pub fn sum_to_precision(&mut self, mantissa_bits: u32, min_exp: i32, max_exp: i32) -> f64 {
+        self.with_small(|xsmall| xsmall.sum_to_precision(mantissa_bits, min_exp, max_exp))
+    }
+
+    /// Returns the exact sum rounded once to `f32`
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.1; 10]);
+    /// assert_eq!(xauto.sum_f32(), 1.0);
+    /// ```
+    pub fn sum_f32(&mut self) -> f32 {
+        self.with_small(|xsmall| xsmall.sum_f32())
+    }
+
+    /// Returns the exact sum rounded once to binary16, as raw bits
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.1; 10]);
+    /// assert_eq!(xauto.sum_f16_bits(), 0x3c00);
+    /// ```
+    pub fn sum_f16_bits(&mut self) -> u16 {
+        self.with_small(|xsmall| xsmall.sum_f16_bits())
+    }
+
+    /// Returns the exact sum rounded once to bfloat16, as raw bits
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.1; 10]);
+    /// assert_eq!(xauto.sum_bf16_bits(), 0x3f80);
+    /// ```
+    pub fn sum_bf16_bits(&mut self) -> u16 {
+        self.with_small(|xsmall| xsmall.sum_bf16_bits())
+    }
+
+    /// Returns the largest `f64` at or below the exact sum, and the smallest `f64` at or above it.
+    /// See [`XsumSmall::sum_interval`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.1; 10]);
+    /// assert_eq!(xauto.sum_interval(), (1.0, 1.0000000000000002));
+    /// ```
+    pub fn sum_interval(&mut self) -> (f64, f64) {
+        self.with_small(|xsmall| xsmall.sum_interval())
+    }
+
+    /// Returns the exact sum as a nonoverlapping expansion, in increasing order of magnitude.
+    /// See [`XsumSmall::to_expansion`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![1.0, 1e-20, 1e-40]);
+    /// assert_eq!(xauto.to_expansion(), vec![1e-40, 1e-20, 1.0]);
+    /// ```
+    pub fn to_expansion(&mut self) -> Vec<f64> {
+        self.with_small(|xsmall| xsmall.to_expansion())
+    }
+
+    /// Adds the exact value of an expansion, a sequence of `f64` terms in any order.
+    /// See [`XsumSmall::add_expansion`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.0; 2_000]); // switch to XsumLarge
+    /// xauto.add_expansion(&[1e-30, 1.0]);
+    /// xauto.add(-1.0);
+    /// assert_eq!(xauto.sum(), 1e-30);
+    /// ```
+    pub fn add_expansion(&mut self, expansion: &[f64]) {
+        self.add_list(expansion);
+    }
+
+    /// Adds the exact value `hi + lo` of a double-double number.
+    /// See [`XsumSmall::add_double_double`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.0; 2_000]); // switch to XsumLarge
+    /// xauto.add_double_double(1.0, 1e-20);
+    /// xauto.add(-1.0);
+    /// assert_eq!(xauto.sum(), 1e-20);
+    /// ```
+    pub fn add_double_double(&mut self, hi: f64, lo: f64) {
+        self.add(hi);
+        self.add(lo);
+    }
+
+    /// Returns the exact accumulated value as a hexadecimal literal, in C99 `%a` style.
+    /// See [`XsumSmall::to_hex_string`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.1; 10]);
+    /// assert_eq!(xauto.to_hex_string(), "0x1.00000000000004p+0");
+    /// ```
+    pub fn to_hex_string(&mut self) -> String {
+        self.with_small(|xsmall| xsmall.to_hex_string())
+    }
+
+    /// Adds the exact value of a hexadecimal floating-point literal.
+    /// See [`XsumSmall::add_hex_str`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseHexError`] if `s` is not a literal that the accumulator can hold exactly.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.1; 10]);
+    /// xauto.add_hex_str("-0x1p-54").unwrap();
+    /// assert_eq!(xauto.to_hex_string(), "0x1p+0");
+    /// ```
+    pub fn add_hex_str(&mut self, s: &str) -> Result<(), ParseHexError> {
+        match &mut self.m_xsum {
+            XsumKind::XSmall(xsmall) => {
+                xsmall.add_hex_str(s)?;
+                self.transform_to_large();
+                Ok(())
+            }
+            XsumKind::XLarge(xlarge) => xlarge.add_hex_str(s),
+        }
+    }
+
+    /// Returns the exact accumulated value as `(sign, magnitude, exponent)`, where the value is
+    /// the integer `magnitude` times 2^`exponent`. See [`XsumSmall::to_scaled_integer`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same [`XsumError`] as `try_sum()` if a NaN or an infinity was added.
+    ///
+    /// ```
+    /// use xsum::{Sign, Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.75; 10]);
+    /// assert_eq!(xauto.to_scaled_integer(), Ok((Sign::Positive, vec![15], -1)));
+    /// ```
+    pub fn to_scaled_integer(&mut self) -> Result<(Sign, Vec<u64>, i64), XsumError> {
+        self.with_small(|xsmall| xsmall.to_scaled_integer())
+    }
+
+    /// Returns the exact accumulated value divided by 2^`exp`, if that is an integer that
+    /// fits in `i128`. See [`XsumSmall::to_i128_scaled`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.75; 10]);
+    /// assert_eq!(xauto.to_i128_scaled(-1), Some(15));
+    /// ```
+    pub fn to_i128_scaled(&mut self, exp: i64) -> Option<i128> {
+        self.with_small(|xsmall| xsmall.to_i128_scaled(exp))
+    }
+
+    /// Creates an accumulator holding the integer `magnitude` times 2^`exp`, with `sign`.
+    /// See [`XsumSmall::from_scaled_integer`].
+    ///
+    /// ```
+    /// use xsum::{Sign, Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::from_scaled_integer(Sign::Negative, &[3], -1).unwrap();
+    /// assert_eq!(xauto.sum(), -1.5);
+    /// ```
+    #[must_use]
+    pub fn from_scaled_integer(sign: Sign, magnitude: &[u64], exp: i64) -> Option<Self> {
+        XsumSmall::from_scaled_integer(sign, magnitude, exp).map(|xsmall| Self {
+            m_xsum: XsumKind::XSmall(xsmall),
+        })
+    }
+
+    /// Returns the exact sum rounded to an integer with `mode`. See [`XsumSmall::round_i128`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same [`XsumError`] as `try_sum()` if a NaN or an infinity was added, and
+    /// [`XsumError::Overflow`] if the rounded sum does not fit in `i128`.
+    ///
+    /// ```
+    /// use xsum::{RoundingMode, Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![1e20; 10]);
+    /// xauto.add(-2.5);
+    /// assert_eq!(xauto.round_i128(RoundingMode::HalfEven), Ok(999_999_999_999_999_999_998));
+    /// assert_eq!(xauto.round_i128(RoundingMode::TowardZero), Ok(999_999_999_999_999_999_997));
+    /// ```
+    pub fn round_i128(&mut self, mode: RoundingMode) -> Result<i128, XsumError> {
+        self.with_small(|xsmall| xsmall.round_i128(mode))
+    }
+
+    /// Returns the exact sum rounded toward negative infinity. See [`XsumSmall::floor_i128`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`XsumError`] as `round_i128()` does.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![1e20; 10]);
+    /// xauto.add(-2.5);
+    /// assert_eq!(xauto.floor_i128(), Ok(999_999_999_999_999_999_997));
+    /// ```
+    pub fn floor_i128(&mut self) -> Result<i128, XsumError> {
+        self.round_i128(RoundingMode::Floor)
+    }
+
+    /// Returns the exact sum rounded toward positive infinity. See [`XsumSmall::ceil_i128`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`XsumError`] as `round_i128()` does.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![1e20; 10]);
+    /// xauto.add(-2.5);
+    /// assert_eq!(xauto.ceil_i128(), Ok(999_999_999_999_999_999_998));
+    /// ```
+    pub fn ceil_i128(&mut self) -> Result<i128, XsumError> {
+        self.round_i128(RoundingMode::Ceil)
+    }
+
+    /// Returns the integer part of the exact sum. See [`XsumSmall::trunc_i128`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`XsumError`] as `round_i128()` does.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![1e20; 10]);
+    /// xauto.add(-2.5);
+    /// assert_eq!(xauto.trunc_i128(), Ok(999_999_999_999_999_999_997));
+    /// ```
+    pub fn trunc_i128(&mut self) -> Result<i128, XsumError> {
+        self.round_i128(RoundingMode::TowardZero)
+    }
+
+    /// Returns the fractional part of the exact sum, rounded once to `f64`.
+    /// See [`XsumSmall::fract`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![1e20; 10]);
+    /// xauto.add(-2.5);
+    /// assert_eq!(xauto.fract(), 0.5);
+    /// ```
+    pub fn fract(&mut self) -> f64 {
+        self.with_small(|xsmall| xsmall.fract())
+    }
+
+    /// Returns the exact sum rounded once to `places` decimal places with `mode`, as
+    /// `(mantissa, scale)`. See [`XsumSmall::to_decimal_rounded`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same [`XsumError`] as `try_sum()` if a NaN or an infinity was added, and
+    /// [`XsumError::Overflow`] if the mantissa does not fit in `i128`.
+    ///
+    /// ```
+    /// use xsum::{RoundingMode, Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.005; 10]);
+    /// xauto.add(0.125);
+    /// assert_eq!(xauto.to_decimal_rounded(2, RoundingMode::HalfEven), Ok((18, 2)));
+    /// ```
+    pub fn to_decimal_rounded(
+        &mut self,
+        places: u32,
+        mode: RoundingMode,
+    ) -> Result<(i128, u32), XsumError> {
+        self.with_small(|xsmall| xsmall.to_decimal_rounded(places, mode))
+    }
+
+    /// Returns the exact sum rounded once to `places` decimal places with `mode`, as a string.
+    /// See [`XsumSmall::to_decimal_string`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same [`XsumError`] as `try_sum()` if a NaN or an infinity was added.
+    ///
+    /// ```
+    /// use xsum::{RoundingMode, Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.005; 10]);
+    /// xauto.add(0.125);
+    /// assert_eq!(xauto.to_decimal_string(3, RoundingMode::HalfEven).unwrap(), "0.175");
+    /// ```
+    pub fn to_decimal_string(
+        &mut self,
+        places: u32,
+        mode: RoundingMode,
+    ) -> Result<String, XsumError> {
+        self.with_small(|xsmall| xsmall.to_decimal_string(places, mode))
+    }
+
+    /// Compares the exact sum with `x`, or returns `None` if either is NaN.
+    /// See [`XsumSmall::cmp_f64`].
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![-1.0, -(2.0_f64.powi(-60))]);
+    /// assert_eq!(xauto.cmp_f64(-1.0), Some(Ordering::Less));
+    /// ```
+    #[must_use]
+    pub fn cmp_f64(&self, x: f64) -> Option<Ordering> {
+        match &self.m_xsum {
+            XsumKind::XSmall(xsmall) => xsmall.cmp_f64(x),
+            XsumKind::XLarge(xlarge) => xlarge.cmp_f64(x),
+        }
+    }
+
+    /// Returns the sign of the exact sum: `1.0`, `-1.0`, or `0.0` if it is exactly zero.
+    /// See [`XsumSmall::signum`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![1.0, f64::NAN]);
+    /// assert!(xauto.signum().is_nan());
+    /// ```
+    pub fn signum(&mut self) -> f64 {
+        match &mut self.m_xsum {
+            XsumKind::XSmall(xsmall) => xsmall.signum(),
+            XsumKind::XLarge(xlarge) => xlarge.signum(),
+        }
+    }
+
+    /// Returns `true` if the exact sum is zero, of either sign. See [`XsumSmall::is_zero`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![1e300, 1.0, -1e300]);
+    /// assert!(!xauto.is_zero());
+    /// ```
+    pub fn is_zero(&mut self) -> bool {
+        match &mut self.m_xsum {
+            XsumKind::XSmall(xsmall) => xsmall.is_zero(),
+            XsumKind::XLarge(xlarge) => xlarge.is_zero(),
+        }
+    }
+
+    /// Returns the exact sum minus `candidate`, rounded once to the nearest `f64`.
+    /// See [`XsumSmall::error_of`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![1e16, 1.0, 1.0]);
+    /// assert_eq!(xauto.error_of(1e16), 2.0);
+    /// assert_eq!(xauto.error_of(f64::INFINITY), f64::NEG_INFINITY);
+    /// ```
+    pub fn error_of(&mut self, candidate: f64) -> f64 {
+        self.with_small(|xsmall| xsmall.error_of(candidate))
+    }
+
+    /// Returns how far `candidate` is from the exact sum, in units in the last place of
+    /// `candidate`, rounded down. See [`XsumSmall::ulps_from`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![1e16, 1.0, 1.0]);
+    /// assert_eq!(xauto.ulps_from(1e16), 1);
+    /// ```
+    pub fn ulps_from(&mut self, candidate: f64) -> u64 {
+        self.with_small(|xsmall| xsmall.ulps_from(candidate))
+    }
+
+    /// Calls `f` with a small accumulator holding the exact value, for comparisons and hashing
+    pub(crate) fn with_exact<R>(&self, f: impl FnOnce(&SmallAccumulator) -> R) -> R {
+        match &self.m_xsum {
+            XsumKind::XSmall(xsmall) => xsmall.with_exact(f),
+            XsumKind::XLarge(xlarge) => xlarge.with_exact(f),
+        }
+    }
+
+    /// Multiplies the accumulated value by 2^`k`. See [`XsumSmall::scale_pow2`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![1.5; 10]);
+    /// xauto.scale_pow2(-1);
+    /// assert_eq!(xauto.sum(), 7.5);
+    /// ```
+    pub fn scale_pow2(&mut self, k: i32) {
+        match &mut self.m_xsum {
+            XsumKind::XSmall(xsmall) => xsmall.scale_pow2(k),
+            XsumKind::XLarge(xlarge) => xlarge.scale_pow2(k),
+        }
+    }
+
+    /// Multiplies the accumulated value by `n` exactly
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.1; 10]);
+    /// xauto.mul_int(10);
+    /// assert_eq!(xauto.sum(), 10.0);
+    /// ```
+    pub fn mul_int(&mut self, n: i64) {
+        match &mut self.m_xsum {
+            XsumKind::XSmall(xsmall) => xsmall.mul_int(n),
+            XsumKind::XLarge(xlarge) => xlarge.mul_int(n),
+        }
+    }
+
+    /// Adds `value` `count` times, exactly and in constant time
+    ///
+    /// The result is the same as calling `add(value)` `count` times.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_repeated(f64::MAX, 4);
+    /// xauto.add_repeated(-f64::MAX, 3);
+    /// assert_eq!(xauto.sum(), f64::MAX);
+    /// ```
+    pub fn add_repeated(&mut self, value: f64, count: u64) {
+        match &mut self.m_xsum {
+            XsumKind::XSmall(xsmall) => {
+                xsmall.add_repeated(value, count);
+                self.transform_to_large();
+            }
+            XsumKind::XLarge(xlarge) => {
+                xlarge.add_repeated(value, count);
+            }
+        }
+    }
+
+    /// Adds each value of `(value, count)` pairs `count` times, as `add_repeated` does.
+    /// See [`XsumSmall::add_repeated_list`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.0; 2_000]); // switch to XsumLarge
+    /// xauto.add_repeated_list(&[(1e16, 1_000), (1.0, 1_000), (-1e16, 1_000)]);
+    /// assert_eq!(xauto.sum(), 1_000.0);
+    /// ```
+    pub fn add_repeated_list(&mut self, pairs: &[(f64, u64)]) {
+        match &mut self.m_xsum {
+            XsumKind::XSmall(xsmall) => {
+                xsmall.add_repeated_list(pairs);
+                self.transform_to_large();
+            }
+            XsumKind::XLarge(xlarge) => {
+                xlarge.add_repeated_list(pairs);
+            }
+        }
+    }
+
+    /// Adds the integer `value` exactly, without rounding it to `f64` first
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_i64(-(1 << 60) - 1);
+    /// xauto.add(1.152921504606847e18);
+    /// assert_eq!(xauto.sum(), -1.0);
+    /// ```
+    pub fn add_i64(&mut self, value: i64) {
+        match &mut self.m_xsum {
+            XsumKind::XSmall(xsmall) => {
+                xsmall.add_i64(value);
+                self.transform_to_large();
+            }
+            XsumKind::XLarge(xlarge) => {
+                xlarge.add_i64(value);
+            }
+        }
+    }
+
+    /// Adds the integers of `vec` exactly, as `add_i64` does. See [`XsumSmall::add_i64_list`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.0; 2_000]); // switch to XsumLarge
+    /// xauto.add_i64_list(&[9_007_199_254_740_993, 1, -2]);
+    /// assert_eq!(xauto.sum(), 9_007_199_254_740_992.0);
+    /// ```
+    pub fn add_i64_list(&mut self, vec: &[i64]) {
+        match &mut self.m_xsum {
+            XsumKind::XSmall(xsmall) => {
+                xsmall.add_i64_list(vec);
+                self.transform_to_large();
+            }
+            XsumKind::XLarge(xlarge) => {
+                xlarge.add_i64_list(vec);
+            }
+        }
+    }
+
+    /// Adds the integer `value` exactly, without rounding it to `f64` first.
+    /// See [`XsumSmall::add_u64`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.0; 2_000]); // switch to XsumLarge
+    /// xauto.add_u64(u64::MAX);
+    /// xauto.add(-1.8446744073709552e19);
+    /// assert_eq!(xauto.sum(), -1.0);
+    /// ```
+    pub fn add_u64(&mut self, value: u64) {
+        match &mut self.m_xsum {
+            XsumKind::XSmall(xsmall) => {
+                xsmall.add_u64(value);
+                self.transform_to_large();
+            }
+            XsumKind::XLarge(xlarge) => {
+                xlarge.add_u64(value);
+            }
+        }
+    }
+
+    /// Adds the integers of `vec` exactly, as `add_u64` does. See [`XsumSmall::add_u64_list`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.0; 2_000]); // switch to XsumLarge
+    /// xauto.add_u64_list(&[u64::MAX, 1]);
+    /// assert_eq!(xauto.sum(), 18_446_744_073_709_551_616.0);
+    /// ```
+    pub fn add_u64_list(&mut self, vec: &[u64]) {
+        match &mut self.m_xsum {
+            XsumKind::XSmall(xsmall) => {
+                xsmall.add_u64_list(vec);
+                self.transform_to_large();
+            }
+            XsumKind::XLarge(xlarge) => {
+                xlarge.add_u64_list(vec);
+            }
+        }
+    }
+
+    /// Adds the integer `value` exactly, without rounding it to `f64` first
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_i128(i128::MAX);
+    /// xauto.add(-1.7014118346046923e38);
+    /// assert_eq!(xauto.sum(), -1.0);
+    /// ```
+    pub fn add_i128(&mut self, value: i128) {
+        match &mut self.m_xsum {
+            XsumKind::XSmall(xsmall) => {
+                xsmall.add_i128(value);
+                self.transform_to_large();
+            }
+            XsumKind::XLarge(xlarge) => {
+                xlarge.add_i128(value);
+            }
+        }
+    }
+
+    /// Adds the integers of `vec` exactly, as `add_i128` does. See [`XsumSmall::add_i128_list`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.0; 2_000]); // switch to XsumLarge
+    /// xauto.add_i128_list(&[1 << 100, 3, -(1 << 100)]);
+    /// assert_eq!(xauto.sum(), 3.0);
+    /// ```
+    pub fn add_i128_list(&mut self, vec: &[i128]) {
+        match &mut self.m_xsum {
+            XsumKind::XSmall(xsmall) => {
+                xsmall.add_i128_list(vec);
+                self.transform_to_large();
+            }
+            XsumKind::XLarge(xlarge) => {
+                xlarge.add_i128_list(vec);
+            }
+        }
+    }
+
+    /// Adds a number of any type that converts to `f64` exactly. See [`XsumSmall::add_generic`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.0; 2_000]); // switch to XsumLarge
+    /// xauto.add_generic(0.1f32);
+    /// xauto.add_generic(-1i32);
+    /// assert_eq!(xauto.sum(), -0.8999999985098839);
+    /// ```
+    #[inline(always)]
+    pub fn add_generic<T: ExactAddend>(&mut self, value: T) {
+        self.add(value.to_f64());
+    }
+
+    /// Adds a slice of numbers of any type that converts to `f64` exactly
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_slice(&[u8::MAX; 2_000]);
+    /// assert_eq!(xauto.sum(), 510_000.0);
+    /// ```
+    pub fn add_slice<T: ExactAddend>(&mut self, vec: &[T]) {
+        match &mut self.m_xsum {
+            XsumKind::XSmall(xsmall) => {
+                xsmall.add_slice(vec);
+                self.transform_to_large();
+            }
+            XsumKind::XLarge(xlarge) => {
+                xlarge.add_slice(vec);
+            }
+        }
+    }
+
+    /// Adds binary16 (IEEE half precision) numbers given as raw bits, exactly.
+    /// See [`XsumSmall::add_f16_bits`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.0; 2_000]); // switch to XsumLarge
+    /// // 1.0, 0.1 rounded to binary16, and the smallest binary16 subnormal
+    /// xauto.add_f16_bits(&[0x3c00, 0x2e66, 0x0001]);
+    /// assert_eq!(xauto.sum(), 1.0999756455421448);
+    /// ```
+    pub fn add_f16_bits(&mut self, bits: &[u16]) {
+        match &mut self.m_xsum {
+            XsumKind::XSmall(xsmall) => {
+                xsmall.add_f16_bits(bits);
+                self.transform_to_large();
+            }
+            XsumKind::XLarge(xlarge) => {
+                xlarge.add_f16_bits(bits);
+            }
+        }
+    }
+
+    /// Adds bfloat16 numbers given as raw bits, exactly. See [`XsumSmall::add_bf16_bits`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto};
+    ///
+    /// let mut xauto = XsumAuto::new();
+    /// xauto.add_list(&vec![0.0; 2_000]); // switch to XsumLarge
+    /// // 1.0, -0.1 rounded to bfloat16, and -Inf
+    /// xauto.add_bf16_bits(&[0x3f80, 0xbdcd]);
+    /// assert_eq!(xauto.sum(), 0.89990234375);
+    /// xauto.add_bf16_bits(&[0xff80]);
+    /// assert_eq!(xauto.sum(), f64::NEG_INFINITY);
+    /// ```
+    pub fn add_bf16_bits(&mut self, bits: &[u16]) {
+        match &mut self.m_xsum {
+            XsumKind::XSmall(xsmall) => {
+                xsmall.add_bf16_bits(bits);
+                self.transform_to_large();
+            }
+            XsumKind::XLarge(xlarge) => {
+                xlarge.add_bf16_bits(bits);
+            }
+        }
+    }
 }
 
 impl Xsum for XsumAuto {
@@ -118,6 +1058,8 @@ impl Xsum for XsumAuto {
         }
     }
 
+    /// Resets the sum, keeping the policy for NaN and infinite inputs
+    ///
     /// ```
     /// use xsum::{Xsum, XsumAuto};
     ///
@@ -131,6 +1073,6 @@ impl Xsum for XsumAuto {
     /// assert!(res.is_sign_negative());
     /// ```
     fn clear(&mut self) {
-        *self = Self::default();
+        *self = Self::with_policy(self.policy());
     }
 }
@@ -1,5 +1,21 @@
+use std::cmp::Ordering;
+
 use crate::{
-    accumulators::large_accumulator::LargeAccumulator, constants::XSUM_MANTISSA_BITS, traits::Xsum,
+    accumulators::{
+        fixed_point::{FixedPoint, HexFloat},
+        large_accumulator::LargeAccumulator,
+        small_accumulator::SmallAccumulator,
+    },
+    constants::{
+        XSUM_BF16_MANTISSA_BITS, XSUM_F16_MANTISSA_BITS, XSUM_MANTISSA_BITS, XSUM_MANTISSA_MASK,
+    },
+    error::XsumError,
+    flags::SumFlags,
+    parse_hex_error::ParseHexError,
+    policy::SpecialValuePolicy,
+    rounding_mode::RoundingMode,
+    sign::Sign,
+    traits::{ExactAddend, Xsum},
     xsum_small::XsumSmall,
 };
 
@@ -26,12 +42,847 @@ impl Default for XsumLarge {
 }
 
 impl XsumLarge {
+    /// Creates an accumulator that handles NaN and infinite inputs according to `policy`
+    ///
+    /// ```
+    /// use xsum::{SpecialValuePolicy, Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::with_policy(SpecialValuePolicy::IgnoreNaN);
+    /// xlarge.add_list(&vec![1.0, f64::NAN, 2.0]);
+    /// assert_eq!(xlarge.sum(), 3.0);
+    /// ```
+    #[must_use]
+    pub fn with_policy(policy: SpecialValuePolicy) -> Self {
+        Self {
+            m_lacc: LargeAccumulator::with_policy(policy),
+        }
+    }
+
+    /// Returns the policy for NaN and infinite inputs
+    ///
+    /// ```
+    /// use xsum::{SpecialValuePolicy, Xsum, XsumLarge};
+    ///
+    /// assert_eq!(XsumLarge::new().policy(), SpecialValuePolicy::Propagate);
+    /// ```
+    #[must_use]
+    pub const fn policy(&self) -> SpecialValuePolicy {
+        self.m_lacc.m_sacc.m_policy
+    }
+
     #[must_use]
     pub fn from_xsum_small(xsmall: XsumSmall) -> Self {
         let mut lacc = LargeAccumulator::new();
         lacc.m_sacc = xsmall.transfer_accumulator();
         Self { m_lacc: lacc }
     }
+
+    /// Moves the large chunks into the small accumulator, and returns a copy of it
+    pub(crate) fn transfer_and_copy_small(&mut self) -> XsumSmall {
+        self.m_lacc.transfer_to_small();
+        XsumSmall::new_with(&self.m_lacc.m_sacc)
+    }
+
+    /// Returns the number of values added so far
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![1.0; 1_000]);
+    /// assert_eq!(xlarge.len(), 1_000);
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.m_lacc.m_sacc.m_size_count
+    }
+
+    /// Returns `true` if no value has been added
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// assert!(xlarge.is_empty());
+    /// xlarge.add(0.0);
+    /// assert!(!xlarge.is_empty());
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.m_lacc.m_sacc.m_size_count == 0
+    }
+
+    /// Returns `true` if a NaN has been added
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![1.0, f64::NAN]);
+    /// assert!(xlarge.has_nan());
+    /// ```
+    #[must_use]
+    pub const fn has_nan(&self) -> bool {
+        self.m_lacc.m_sacc.m_nan != 0
+    }
+
+    /// Returns `true` if `+Inf` or `-Inf` has been added
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![1.0, f64::INFINITY]);
+    /// assert!(xlarge.has_infinity());
+    /// ```
+    #[must_use]
+    pub const fn has_infinity(&self) -> bool {
+        self.m_lacc.m_sacc.m_inf != 0
+    }
+
+    /// Returns the 52-bit mantissa field of the NaN that `sum()` would return,
+    /// or `None` if no NaN has been added. See [`XsumSmall::nan_payload`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add(f64::from_bits(0x7ff8_0000_0000_002a));
+    /// assert_eq!(xlarge.nan_payload(), Some(0x8_0000_0000_002a));
+    /// ```
+    #[must_use]
+    pub const fn nan_payload(&self) -> Option<u64> {
+        if self.m_lacc.m_sacc.m_nan == 0 {
+            None
+        } else {
+            Some((self.m_lacc.m_sacc.m_nan & XSUM_MANTISSA_MASK) as u64)
+        }
+    }
+
+    /// Returns a human-readable listing of the large chunks that hold values
+    /// not yet transferred, followed by the non-zero chunks of the small accumulator
+    ///
+    /// The format is meant for debugging and may change between versions.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![1.0; 10]);
+    /// let dump = xlarge.debug_dump();
+    /// assert!(dump.contains("chunk[1023]"));
+    /// ```
+    #[must_use]
+    pub fn debug_dump(&self) -> String {
+        let mut out = String::new();
+        self.m_lacc.write_dump(&mut out);
+        out
+    }
+
+    /// Returns the same value as `sum()` if it is finite, otherwise the reason why it is not.
+    /// See [`XsumSmall::try_sum`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumError, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![1.0; 1_000]);
+    /// assert_eq!(xlarge.try_sum(), Ok(1_000.0));
+    ///
+    /// xlarge.add_list(&vec![f64::INFINITY, f64::NEG_INFINITY]);
+    /// assert_eq!(xlarge.try_sum(), Err(XsumError::OppositeInfinities));
+    /// ```
+    pub fn try_sum(&mut self) -> Result<f64, XsumError> {
+        self.transfer_and_copy_small().try_sum()
+    }
+
+    /// Returns the same value as `sum()`, together with flags telling whether it had to be rounded.
+    /// See [`XsumSmall::sum_with_flags`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![0.1; 1_000]);
+    /// let (sum, flags) = xlarge.sum_with_flags();
+    /// assert_eq!(sum, 100.0);
+    /// assert!(flags.inexact);
+    /// ```
+    pub fn sum_with_flags(&mut self) -> (f64, SumFlags) {
+        self.transfer_and_copy_small().sum_with_flags()
+    }
+
+    /// Returns the exact sum rounded to 53 significant bits as `(mantissa, exponent)`,
+    /// with an exponent that is not limited to the range of `f64`.
+    /// See [`XsumSmall::sum_extended`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![1e308; 1_000]);
+    /// let (mantissa, exponent) = xlarge.sum_extended();
+    /// assert_eq!(exponent, 1034);
+    /// assert!(0.5 <= mantissa && mantissa < 1.0);
+    /// ```
+    pub fn sum_extended(&mut self) -> (f64, i64) {
+        self.transfer_and_copy_small().sum_extended()
+    }
+
+    /// Returns the exact sum rounded once to a narrower binary format.
+    /// See [`XsumSmall::sum_to_precision`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mantissa_bits` is more than 52, or if the exponents are not in order
+    /// within -1022 to 1023.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![0.1; 1_000]);
+    /// // With three bits of mantissa, 100 is half way between 96 and 104. The exact sum is a
+    /// // little above 100, so it rounds up, while rounding the f64 sum 100.0 would give 96.
+    /// assert_eq!(xlarge.sum(), 100.0);
+    /// assert_eq!(xlarge.sum_to_precision(3, -14, 15), 104.0);
+    /// ```
+    pub fn sum_to_precision(&mut self, mantissa_bits: u32, min_exp: i32, max_exp: i32) -> f64 {
+        self.transfer_and_copy_small()
+            .sum_to_precision(mantissa_bits, min_exp, max_exp)
+    }
+
+    /// Returns the exact sum rounded once to `f32`
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![0.1; 1_000]);
+    /// assert_eq!(xlarge.sum_f32(), 100.0);
+    /// ```
+    pub fn sum_f32(&mut self) -> f32 {
+        self.transfer_and_copy_small().sum_f32()
+    }
+
+    /// Returns the exact sum rounded once to binary16, as raw bits
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![0.1; 1_000]);
+    /// assert_eq!(xlarge.sum_f16_bits(), 0x5640);
+    /// ```
+    pub fn sum_f16_bits(&mut self) -> u16 {
+        self.transfer_and_copy_small().sum_f16_bits()
+    }
+
+    /// Returns the exact sum rounded once to bfloat16, as raw bits
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![0.1; 1_000]);
+    /// assert_eq!(xlarge.sum_bf16_bits(), 0x42c8);
+    /// ```
+    pub fn sum_bf16_bits(&mut self) -> u16 {
+        self.transfer_and_copy_small().sum_bf16_bits()
+    }
+
+    /// Returns the largest `f64` at or below the exact sum, and the smallest `f64` at or above it.
+    /// See [`XsumSmall::sum_interval`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![0.1; 1_000]);
+    /// assert_eq!(xlarge.sum_interval(), (100.0, 100.00000000000001));
+    /// ```
+    pub fn sum_interval(&mut self) -> (f64, f64) {
+        self.transfer_and_copy_small().sum_interval()
+    }
+
+    /// Returns the exact sum as a nonoverlapping expansion, in increasing order of magnitude.
+    /// See [`XsumSmall::to_expansion`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![0.1; 1_000]);
+    /// let expansion = xlarge.to_expansion();
+    /// assert_eq!(expansion.last(), Some(&100.0));
+    /// assert_eq!(expansion.len(), 2);
+    /// ```
+    pub fn to_expansion(&mut self) -> Vec<f64> {
+        self.transfer_and_copy_small().to_expansion()
+    }
+
+    /// Adds the exact value of an expansion, a sequence of `f64` terms in any order
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_expansion(&[1e-30, 1.0]);
+    /// xlarge.add(-1.0);
+    /// assert_eq!(xlarge.sum(), 1e-30);
+    /// ```
+    pub fn add_expansion(&mut self, expansion: &[f64]) {
+        self.add_list(expansion);
+    }
+
+    /// Adds the exact value `hi + lo` of a double-double number
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_double_double(1.0, 1e-20);
+    /// xlarge.add(-1.0);
+    /// assert_eq!(xlarge.sum(), 1e-20);
+    /// ```
+    pub fn add_double_double(&mut self, hi: f64, lo: f64) {
+        self.add(hi);
+        self.add(lo);
+    }
+
+    /// Returns the exact accumulated value as a hexadecimal literal, in C99 `%a` style.
+    /// See [`XsumSmall::to_hex_string`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![0.1; 1_000]);
+    /// assert_eq!(xlarge.to_hex_string(), "0x1.900000000000064p+6");
+    /// ```
+    pub fn to_hex_string(&mut self) -> String {
+        self.transfer_and_copy_small().to_hex_string()
+    }
+
+    /// Adds the exact value of a hexadecimal floating-point literal.
+    /// See [`XsumSmall::add_hex_str`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseHexError`] if `s` is not a literal that the accumulator can hold exactly.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![0.1; 1_000]);
+    /// xlarge.add_hex_str("-0x1.9p-48").unwrap();
+    /// assert_eq!(xlarge.to_hex_string(), "0x1.9p+6");
+    /// ```
+    pub fn add_hex_str(&mut self, s: &str) -> Result<(), ParseHexError> {
+        match HexFloat::parse(s)? {
+            HexFloat::Finite(fixed_point) => self.m_lacc.m_sacc.add_fixed_point(&fixed_point),
+            HexFloat::Special(value) => self.add(value),
+        }
+        Ok(())
+    }
+
+    /// Returns the exact accumulated value as `(sign, magnitude, exponent)`, where the value is
+    /// the integer `magnitude` times 2^`exponent`. See [`XsumSmall::to_scaled_integer`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same [`XsumError`] as `try_sum()` if a NaN or an infinity was added.
+    ///
+    /// ```
+    /// use xsum::{Sign, Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![0.75; 1_000]);
+    /// assert_eq!(xlarge.to_scaled_integer(), Ok((Sign::Positive, vec![375], 1)));
+    /// ```
+    pub fn to_scaled_integer(&mut self) -> Result<(Sign, Vec<u64>, i64), XsumError> {
+        self.transfer_and_copy_small().to_scaled_integer()
+    }
+
+    /// Returns the exact accumulated value divided by 2^`exp`, if that is an integer that
+    /// fits in `i128`. See [`XsumSmall::to_i128_scaled`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![0.75; 1_000]);
+    /// assert_eq!(xlarge.to_i128_scaled(-2), Some(3_000));
+    /// ```
+    pub fn to_i128_scaled(&mut self, exp: i64) -> Option<i128> {
+        self.transfer_and_copy_small().to_i128_scaled(exp)
+    }
+
+    /// Creates an accumulator holding the integer `magnitude` times 2^`exp`, with `sign`.
+    /// See [`XsumSmall::from_scaled_integer`].
+    ///
+    /// ```
+    /// use xsum::{Sign, Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::from_scaled_integer(Sign::Negative, &[3], -1).unwrap();
+    /// assert_eq!(xlarge.sum(), -1.5);
+    /// ```
+    #[must_use]
+    pub fn from_scaled_integer(sign: Sign, magnitude: &[u64], exp: i64) -> Option<Self> {
+        let fixed_point: FixedPoint = FixedPoint::from_limbs(sign, magnitude, exp)?;
+        let mut xsum_large: Self = Self::new();
+        xsum_large.m_lacc.m_sacc.add_fixed_point(&fixed_point);
+        Some(xsum_large)
+    }
+
+    /// Returns the exact sum rounded to an integer with `mode`. See [`XsumSmall::round_i128`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same [`XsumError`] as `try_sum()` if a NaN or an infinity was added, and
+    /// [`XsumError::Overflow`] if the rounded sum does not fit in `i128`.
+    ///
+    /// ```
+    /// use xsum::{RoundingMode, Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![1e20; 1_000]);
+    /// xlarge.add(-2.5);
+    /// assert_eq!(xlarge.round_i128(RoundingMode::HalfEven), Ok(99_999_999_999_999_999_999_998));
+    /// assert_eq!(xlarge.round_i128(RoundingMode::TowardZero), Ok(99_999_999_999_999_999_999_997));
+    /// ```
+    pub fn round_i128(&mut self, mode: RoundingMode) -> Result<i128, XsumError> {
+        self.transfer_and_copy_small().round_i128(mode)
+    }
+
+    /// Returns the exact sum rounded toward negative infinity. See [`XsumSmall::floor_i128`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`XsumError`] as `round_i128()` does.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![1e20; 1_000]);
+    /// xlarge.add(-2.5);
+    /// assert_eq!(xlarge.floor_i128(), Ok(99_999_999_999_999_999_999_997));
+    /// ```
+    pub fn floor_i128(&mut self) -> Result<i128, XsumError> {
+        self.round_i128(RoundingMode::Floor)
+    }
+
+    /// Returns the exact sum rounded toward positive infinity. See [`XsumSmall::ceil_i128`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`XsumError`] as `round_i128()` does.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![1e20; 1_000]);
+    /// xlarge.add(-2.5);
+    /// assert_eq!(xlarge.ceil_i128(), Ok(99_999_999_999_999_999_999_998));
+    /// ```
+    pub fn ceil_i128(&mut self) -> Result<i128, XsumError> {
+        self.round_i128(RoundingMode::Ceil)
+    }
+
+    /// Returns the integer part of the exact sum. See [`XsumSmall::trunc_i128`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`XsumError`] as `round_i128()` does.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![1e20; 1_000]);
+    /// xlarge.add(-2.5);
+    /// assert_eq!(xlarge.trunc_i128(), Ok(99_999_999_999_999_999_999_997));
+    /// ```
+    pub fn trunc_i128(&mut self) -> Result<i128, XsumError> {
+        self.round_i128(RoundingMode::TowardZero)
+    }
+
+    /// Returns the fractional part of the exact sum, rounded once to `f64`.
+    /// See [`XsumSmall::fract`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![1e20; 1_000]);
+    /// xlarge.add(-2.5);
+    /// assert_eq!(xlarge.fract(), 0.5);
+    /// ```
+    pub fn fract(&mut self) -> f64 {
+        self.transfer_and_copy_small().fract()
+    }
+
+    /// Returns the exact sum rounded once to `places` decimal places with `mode`, as
+    /// `(mantissa, scale)`. See [`XsumSmall::to_decimal_rounded`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same [`XsumError`] as `try_sum()` if a NaN or an infinity was added, and
+    /// [`XsumError::Overflow`] if the mantissa does not fit in `i128`.
+    ///
+    /// ```
+    /// use xsum::{RoundingMode, Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![0.005; 1_000]);
+    /// xlarge.add(0.125);
+    /// assert_eq!(xlarge.to_decimal_rounded(2, RoundingMode::HalfEven), Ok((513, 2)));
+    /// ```
+    pub fn to_decimal_rounded(
+        &mut self,
+        places: u32,
+        mode: RoundingMode,
+    ) -> Result<(i128, u32), XsumError> {
+        self.transfer_and_copy_small()
+            .to_decimal_rounded(places, mode)
+    }
+
+    /// Returns the exact sum rounded once to `places` decimal places with `mode`, as a string.
+    /// See [`XsumSmall::to_decimal_string`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same [`XsumError`] as `try_sum()` if a NaN or an infinity was added.
+    ///
+    /// ```
+    /// use xsum::{RoundingMode, Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![0.005; 1_000]);
+    /// xlarge.add(0.125);
+    /// assert_eq!(xlarge.to_decimal_string(3, RoundingMode::HalfEven).unwrap(), "5.125");
+    /// ```
+    pub fn to_decimal_string(
+        &mut self,
+        places: u32,
+        mode: RoundingMode,
+    ) -> Result<String, XsumError> {
+        self.transfer_and_copy_small()
+            .to_decimal_string(places, mode)
+    }
+
+    /// Compares the exact sum with `x`, or returns `None` if either is NaN.
+    /// See [`XsumSmall::cmp_f64`].
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![2.0_f64.powi(-60); 1_000]);
+    /// xlarge.add(1.0);
+    /// assert_eq!(xlarge.cmp_f64(1.0), Some(Ordering::Greater));
+    /// ```
+    #[must_use]
+    pub fn cmp_f64(&self, x: f64) -> Option<Ordering> {
+        self.m_lacc.to_small().cmp_f64(x)
+    }
+
+    /// Returns the sign of the exact sum: `1.0`, `-1.0`, or `0.0` if it is exactly zero.
+    /// See [`XsumSmall::signum`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![-1e-300; 1_000]);
+    /// assert_eq!(xlarge.signum(), -1.0);
+    /// ```
+    pub fn signum(&mut self) -> f64 {
+        self.m_lacc.transfer_to_small();
+        self.m_lacc.m_sacc.value_signum()
+    }
+
+    /// Returns `true` if the exact sum is zero, of either sign. See [`XsumSmall::is_zero`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![0.1; 1_000]);
+    /// xlarge.add_list(&vec![-0.1; 1_000]);
+    /// assert!(xlarge.is_zero());
+    /// ```
+    pub fn is_zero(&mut self) -> bool {
+        self.signum() == 0.0
+    }
+
+    /// Returns the exact sum minus `candidate`, rounded once to the nearest `f64`.
+    /// See [`XsumSmall::error_of`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let vec = vec![0.1; 1_000];
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec);
+    /// let naive: f64 = vec.iter().sum();
+    /// assert_eq!(naive, 99.9999999999986);
+    /// assert_eq!(xlarge.error_of(naive), 1.4124257319281242e-12);
+    /// ```
+    pub fn error_of(&mut self, candidate: f64) -> f64 {
+        self.transfer_and_copy_small().error_of(candidate)
+    }
+
+    /// Returns how far `candidate` is from the exact sum, in units in the last place of
+    /// `candidate`, rounded down. See [`XsumSmall::ulps_from`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let vec = vec![0.1; 1_000];
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec);
+    /// let naive: f64 = vec.iter().sum();
+    /// assert_eq!(xlarge.ulps_from(naive), 99);
+    /// ```
+    pub fn ulps_from(&mut self, candidate: f64) -> u64 {
+        self.transfer_and_copy_small().ulps_from(candidate)
+    }
+
+    /// Calls `f` with a small accumulator holding the exact value, for comparisons and hashing
+    pub(crate) fn with_exact<R>(&self, f: impl FnOnce(&SmallAccumulator) -> R) -> R {
+        f(&self.m_lacc.to_small())
+    }
+
+    /// Multiplies the accumulated value by 2^`k`. See [`XsumSmall::scale_pow2`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![0.1; 1_000]);
+    /// xlarge.scale_pow2(1);
+    /// assert_eq!(xlarge.sum(), 200.0);
+    /// ```
+    pub fn scale_pow2(&mut self, k: i32) {
+        self.m_lacc.transfer_to_small();
+        self.m_lacc.m_sacc.scale_pow2(i64::from(k));
+    }
+
+    /// Multiplies the accumulated value by `n` exactly
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![0.1; 1_000]);
+    /// xlarge.mul_int(3);
+    /// assert_eq!(xlarge.sum(), 300.0);
+    /// ```
+    pub fn mul_int(&mut self, n: i64) {
+        self.m_lacc.transfer_to_small();
+        self.m_lacc.m_sacc.mul_int(n);
+    }
+
+    /// Adds `value` `count` times, exactly and in constant time
+    ///
+    /// The result is the same as calling `add(value)` `count` times.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_list(&vec![0.1; 1_000]);
+    /// xlarge.add_repeated(-0.1, 999);
+    /// assert_eq!(xlarge.sum(), 0.1);
+    /// ```
+    pub fn add_repeated(&mut self, value: f64, count: u64) {
+        self.m_lacc.m_sacc.add_repeated(value, count);
+    }
+
+    /// Adds each value of `(value, count)` pairs `count` times, as `add_repeated` does
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_repeated_list(&[(1e16, 1_000), (1.0, 1_000), (-1e16, 1_000)]);
+    /// assert_eq!(xlarge.sum(), 1_000.0);
+    /// ```
+    pub fn add_repeated_list(&mut self, pairs: &[(f64, u64)]) {
+        for &(value, count) in pairs {
+            self.m_lacc.m_sacc.add_repeated(value, count);
+        }
+    }
+
+    /// Adds the integer `value` exactly, without rounding it to `f64` first
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_i64(i64::MAX);
+    /// xlarge.add(-9.223372036854775e18);
+    /// assert_eq!(xlarge.sum(), 1_023.0);
+    /// ```
+    pub fn add_i64(&mut self, value: i64) {
+        self.m_lacc
+            .m_sacc
+            .add_integer(value < 0, value.unsigned_abs().into());
+    }
+
+    /// Adds the integers of `vec` exactly, as `add_i64` does
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_i64_list(&[9_007_199_254_740_993, 1, -2]);
+    /// assert_eq!(xlarge.sum(), 9_007_199_254_740_992.0);
+    /// ```
+    pub fn add_i64_list(&mut self, vec: &[i64]) {
+        for &value in vec {
+            self.add_i64(value);
+        }
+    }
+
+    /// Adds the integer `value` exactly, without rounding it to `f64` first
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_u64(u64::MAX);
+    /// xlarge.add(-1.8446744073709552e19);
+    /// assert_eq!(xlarge.sum(), -1.0);
+    /// ```
+    pub fn add_u64(&mut self, value: u64) {
+        self.m_lacc.m_sacc.add_integer(false, value.into());
+    }
+
+    /// Adds the integers of `vec` exactly, as `add_u64` does
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_u64_list(&[u64::MAX, 1]);
+    /// assert_eq!(xlarge.sum(), 18_446_744_073_709_551_616.0);
+    /// ```
+    pub fn add_u64_list(&mut self, vec: &[u64]) {
+        for &value in vec {
+            self.add_u64(value);
+        }
+    }
+
+    /// Adds the integer `value` exactly, without rounding it to `f64` first
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_i128(i128::MIN);
+    /// xlarge.add(1.7014118346046923e38);
+    /// assert_eq!(xlarge.sum(), 0.0);
+    /// ```
+    pub fn add_i128(&mut self, value: i128) {
+        self.m_lacc
+            .m_sacc
+            .add_integer(value < 0, value.unsigned_abs());
+    }
+
+    /// Adds the integers of `vec` exactly, as `add_i128` does
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_i128_list(&[1 << 100, 3, -(1 << 100)]);
+    /// assert_eq!(xlarge.sum(), 3.0);
+    /// ```
+    pub fn add_i128_list(&mut self, vec: &[i128]) {
+        for &value in vec {
+            self.add_i128(value);
+        }
+    }
+
+    /// Adds a number of any type that converts to `f64` exactly
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_generic(0.1f32);
+    /// xlarge.add_generic(-1i32);
+    /// assert_eq!(xlarge.sum(), -0.8999999985098839);
+    /// ```
+    #[inline(always)]
+    pub fn add_generic<T: ExactAddend>(&mut self, value: T) {
+        self.add(value.to_f64());
+    }
+
+    /// Adds a slice of numbers of any type that converts to `f64` exactly
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// xlarge.add_slice(&[0.1f32; 1_000]);
+    /// assert_eq!(xlarge.sum(), 100.00000149011612);
+    /// ```
+    pub fn add_slice<T: ExactAddend>(&mut self, vec: &[T]) {
+        for &value in vec {
+            self.add(value.to_f64());
+        }
+    }
+
+    /// Adds binary16 (IEEE half precision) numbers given as raw bits, exactly
+    ///
+    /// Subnormals, infinities and NaN payloads are decoded as for `f64` inputs.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// // 1.0, 0.1 rounded to binary16, and the smallest binary16 subnormal
+    /// xlarge.add_f16_bits(&[0x3c00, 0x2e66, 0x0001]);
+    /// assert_eq!(xlarge.sum(), 1.0999756455421448);
+    /// ```
+    pub fn add_f16_bits(&mut self, bits: &[u16]) {
+        for &value in bits {
+            self.m_lacc
+                .m_sacc
+                .add_half_bits(value, XSUM_F16_MANTISSA_BITS);
+        }
+    }
+
+    /// Adds bfloat16 numbers given as raw bits, exactly
+    ///
+    /// Subnormals, infinities and NaN payloads are decoded as for `f64` inputs.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge};
+    ///
+    /// let mut xlarge = XsumLarge::new();
+    /// // 1.0, -0.1 rounded to bfloat16, and -Inf
+    /// xlarge.add_bf16_bits(&[0x3f80, 0xbdcd]);
+    /// assert_eq!(xlarge.sum(), 0.89990234375);
+    /// xlarge.add_bf16_bits(&[0xff80]);
+    /// assert_eq!(xlarge.sum(), f64::NEG_INFINITY);
+    /// ```
+    pub fn add_bf16_bits(&mut self, bits: &[u16]) {
+        for &value in bits {
+            self.m_lacc
+                .m_sacc
+                .add_half_bits(value, XSUM_BF16_MANTISSA_BITS);
+        }
+    }
 }
 
 impl Xsum for XsumLarge {
@@ -100,11 +951,11 @@ impl Xsum for XsumLarge {
     /// assert_eq!(xlarge.sum(), 1_000.0);
     /// ```
     fn sum(&mut self) -> f64 {
-        self.m_lacc.transfer_to_small();
-        let mut xsum_smal = XsumSmall::new_with(&self.m_lacc.m_sacc);
-        xsum_smal.sum()
+        self.transfer_and_copy_small().sum()
     }
 
+    /// Resets the sum, keeping the policy for NaN and infinite inputs
+    ///
     /// ```
     /// use xsum::{Xsum, XsumLarge};
     ///
@@ -118,6 +969,6 @@ impl Xsum for XsumLarge {
     /// assert!(res.is_sign_negative());
     /// ```
     fn clear(&mut self) {
-        *self = Self::default();
+        *self = Self::with_policy(self.policy());
     }
 }
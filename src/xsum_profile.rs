@@ -0,0 +1,380 @@
+use std::fmt::Write;
+
+use crate::{
+    constants::{XSUM_EXP_BIAS, XSUM_EXP_MASK, XSUM_MANTISSA_BITS, XSUM_MANTISSA_MASK},
+    traits::Xsum,
+    XsumAuto,
+};
+
+// Exponent of the smallest subnormal, the lowest bin of the histogram
+const XSUM_PROFILE_MIN_EXP: i32 = 1 - (XSUM_EXP_BIAS + XSUM_MANTISSA_BITS) as i32;
+// One bin for each exponent of a finite non-zero f64, from -1074 to 1023
+const XSUM_PROFILE_BINS: usize = (XSUM_EXP_MASK - 1 + XSUM_MANTISSA_BITS) as usize;
+
+/// XsumProfile sums like `XsumAuto`, and also records how ill-conditioned the sum is
+///
+/// Besides the exact sum, it keeps the exact sum of absolute values, from which the
+/// condition number Σ|x| / |Σx| follows, and counts the values by exponent, sign and
+/// kind. This costs a second exact sum and a few counters per value, so it is opt-in:
+/// use it in place of another accumulator when a total looks suspicious.
+///
+/// The histogram is counted here rather than read from the chunks of `XsumLarge`. Its
+/// used-chunk flags only tell which exponents occurred, not how often, its per-chunk counts
+/// start over each time a chunk is moved into the small accumulator, and a sum that stays
+/// small never has them. The subnormals of each sign also share one chunk, while each gets
+/// the bin of its own exponent here.
+///
+/// # Example
+///
+/// ```
+/// use xsum::{Xsum, XsumProfile};
+///
+/// let mut xprofile = XsumProfile::new();
+/// xprofile.add_list(&vec![1e16, 1.0, -1e16, 0.0]);
+/// assert_eq!(xprofile.sum(), 1.0);
+/// assert_eq!(xprofile.condition_number(), 2e16);
+/// assert_eq!(xprofile.min_exponent(), Some(0));
+/// assert_eq!(xprofile.max_exponent(), Some(53));
+/// assert_eq!(xprofile.negative_count(), 1);
+/// assert_eq!(xprofile.zero_count(), 1);
+/// ```
+#[cfg_attr(debug_assertions, derive(Debug))]
+pub struct XsumProfile {
+    m_xsum: XsumAuto,
+    m_abs_xsum: XsumAuto,
+    m_histogram: Vec<u64>,
+    m_zero_count: u64,
+    m_subnormal_count: u64,
+    m_negative_count: u64,
+}
+
+impl Default for XsumProfile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl XsumProfile {
+    /// Returns the exact sum of the absolute values, rounded once to `f64`
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumProfile};
+    ///
+    /// let mut xprofile = XsumProfile::new();
+    /// xprofile.add_list(&vec![0.1, -0.2, 0.3]);
+    /// assert_eq!(xprofile.abs_sum(), 0.6);
+    /// ```
+    pub fn abs_sum(&mut self) -> f64 {
+        self.m_abs_xsum.sum()
+    }
+
+    /// Returns the condition number of the sum, Σ|x| / |Σx|
+    ///
+    /// It is 1 when all values have the same sign, and grows as they cancel: about that
+    /// many times the rounding error of each value ends up in the sum of a naive loop.
+    /// Both sums are exact and rounded once before dividing, even beyond the range of `f64`.
+    /// An exact sum of zero gives infinity, and NaN is returned if no non-zero value was
+    /// added, or if a NaN or an infinity was.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumProfile};
+    ///
+    /// let mut xprofile = XsumProfile::new();
+    /// xprofile.add_list(&vec![1.0, 2.0, 3.0]);
+    /// assert_eq!(xprofile.condition_number(), 1.0);
+    ///
+    /// xprofile.add(-6.0);
+    /// assert_eq!(xprofile.condition_number(), f64::INFINITY);
+    /// ```
+    pub fn condition_number(&mut self) -> f64 {
+        let (abs_mantissa, abs_exp): (f64, i64) = self.m_abs_xsum.sum_extended();
+        let (mantissa, exp): (f64, i64) = self.m_xsum.sum_extended();
+        if !abs_mantissa.is_finite() || !mantissa.is_finite() || abs_mantissa == 0.0 {
+            return f64::NAN;
+        }
+        if mantissa == 0.0 {
+            return f64::INFINITY;
+        }
+
+        // The ratio of the mantissas is in (0.5, 2), and the scale is applied in two
+        // steps so that it does not overflow before the product would.
+        let scale: i32 = (abs_exp - exp) as i32;
+        abs_mantissa / mantissa.abs() * 2.0_f64.powi(scale / 2) * 2.0_f64.powi(scale - scale / 2)
+    }
+
+    /// Returns the cancellation ratio of the sum, 1 - |Σx| / Σ|x|
+    ///
+    /// It is 0 when all values have the same sign and 1 when they cancel out exactly, and
+    /// NaN when the condition number is.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumProfile};
+    ///
+    /// let mut xprofile = XsumProfile::new();
+    /// xprofile.add_list(&vec![3.0, -1.0]);
+    /// assert_eq!(xprofile.cancellation_ratio(), 0.5);
+    /// ```
+    pub fn cancellation_ratio(&mut self) -> f64 {
+        1.0 - 1.0 / self.condition_number()
+    }
+
+    /// Returns the largest exponent `e`, with 2^`e` <= |x|, of the finite non-zero values
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumProfile};
+    ///
+    /// let mut xprofile = XsumProfile::new();
+    /// assert_eq!(xprofile.max_exponent(), None);
+    /// xprofile.add_list(&vec![0.75, -1e10, f64::INFINITY]);
+    /// assert_eq!(xprofile.max_exponent(), Some(33));
+    /// ```
+    #[must_use]
+    pub fn max_exponent(&self) -> Option<i32> {
+        self.m_histogram
+            .iter()
+            .rposition(|&count| count != 0)
+            .map(|bin| bin as i32 + XSUM_PROFILE_MIN_EXP)
+    }
+
+    /// Returns the smallest exponent `e`, with 2^`e` <= |x|, of the finite non-zero values
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumProfile};
+    ///
+    /// let mut xprofile = XsumProfile::new();
+    /// xprofile.add_list(&vec![0.75, -1e10, 5e-324, 0.0]);
+    /// assert_eq!(xprofile.min_exponent(), Some(-1074));
+    /// ```
+    #[must_use]
+    pub fn min_exponent(&self) -> Option<i32> {
+        self.m_histogram
+            .iter()
+            .position(|&count| count != 0)
+            .map(|bin| bin as i32 + XSUM_PROFILE_MIN_EXP)
+    }
+
+    /// Returns how many finite non-zero values were added for each exponent `e`, with
+    /// 2^`e` <= |x| < 2^(`e` + 1), as `(e, count)` pairs in increasing order of `e`
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumProfile};
+    ///
+    /// let mut xprofile = XsumProfile::new();
+    /// xprofile.add_list(&vec![1.0, -1.5, 0.25, 0.0, 3.0]);
+    /// assert_eq!(xprofile.exponent_histogram(), vec![(-2, 1), (0, 2), (1, 1)]);
+    /// ```
+    #[must_use]
+    pub fn exponent_histogram(&self) -> Vec<(i32, u64)> {
+        self.m_histogram
+            .iter()
+            .enumerate()
+            .filter(|&(_, &count)| count != 0)
+            .map(|(bin, &count)| (bin as i32 + XSUM_PROFILE_MIN_EXP, count))
+            .collect()
+    }
+
+    /// Returns the number of zeros, of either sign, that were added
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumProfile};
+    ///
+    /// let mut xprofile = XsumProfile::new();
+    /// xprofile.add_list(&vec![0.0, -0.0, 1.0]);
+    /// assert_eq!(xprofile.zero_count(), 2);
+    /// ```
+    #[must_use]
+    pub const fn zero_count(&self) -> u64 {
+        self.m_zero_count
+    }
+
+    /// Returns the number of subnormal values that were added
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumProfile};
+    ///
+    /// let mut xprofile = XsumProfile::new();
+    /// xprofile.add_list(&vec![5e-324, -1e-310, f64::MIN_POSITIVE]);
+    /// assert_eq!(xprofile.subnormal_count(), 2);
+    /// ```
+    #[must_use]
+    pub const fn subnormal_count(&self) -> u64 {
+        self.m_subnormal_count
+    }
+
+    /// Returns the number of values below zero that were added, including `-Inf`
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumProfile};
+    ///
+    /// let mut xprofile = XsumProfile::new();
+    /// xprofile.add_list(&vec![-1.0, -0.0, f64::NEG_INFINITY, f64::NAN]);
+    /// assert_eq!(xprofile.negative_count(), 2);
+    /// ```
+    #[must_use]
+    pub const fn negative_count(&self) -> u64 {
+        self.m_negative_count
+    }
+
+    /// Returns the number of values added so far
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumProfile};
+    ///
+    /// let mut xprofile = XsumProfile::new();
+    /// xprofile.add_list(&vec![1.0, f64::NAN]);
+    /// assert_eq!(xprofile.len(), 2);
+    /// ```
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.m_xsum.len()
+    }
+
+    /// Returns `true` if no value has been added
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumProfile};
+    ///
+    /// let mut xprofile = XsumProfile::new();
+    /// assert!(xprofile.is_empty());
+    /// ```
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.m_xsum.is_empty()
+    }
+
+    /// Returns a human-readable report of the sum and its profile, one item per line
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumProfile};
+    ///
+    /// let mut xprofile = XsumProfile::new();
+    /// xprofile.add_list(&vec![1e16, 1.0, -1e16]);
+    /// let report = xprofile.report();
+    /// assert!(report.starts_with("sum: 1\n"));
+    /// assert!(report.contains("condition number: 20000000000000000\n"));
+    /// assert!(report.contains("  2^53: 2\n"));
+    /// ```
+    pub fn report(&mut self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "sum: {}", self.sum());
+        let _ = writeln!(out, "sum of absolute values: {}", self.abs_sum());
+        let _ = writeln!(out, "condition number: {}", self.condition_number());
+        let _ = writeln!(
+            out,
+            "values: {} ({} negative, {} zero, {} subnormal)",
+            self.len(),
+            self.m_negative_count,
+            self.m_zero_count,
+            self.m_subnormal_count
+        );
+        let _ = writeln!(out, "exponent histogram:");
+        for (exp, count) in self.exponent_histogram() {
+            let _ = writeln!(out, "  2^{exp}: {count}");
+        }
+        out
+    }
+
+    /// Counts `value` in the histogram and the counters.
+    #[inline(always)]
+    fn record(&mut self, value: f64) {
+        if value < 0.0 {
+            self.m_negative_count += 1;
+        }
+
+        let bits: u64 = value.to_bits();
+        let exp: u64 = (bits >> XSUM_MANTISSA_BITS) & XSUM_EXP_MASK as u64;
+        let mantissa: u64 = bits & XSUM_MANTISSA_MASK as u64;
+        if exp == XSUM_EXP_MASK as u64 {
+            return; // Inf or NaN
+        }
+        let bin: usize = if exp != 0 {
+            (exp - 1) as usize + XSUM_MANTISSA_BITS as usize
+        } else if mantissa != 0 {
+            // the exponent of a subnormal is that of its leading 1 bit
+            self.m_subnormal_count += 1;
+            (u64::BITS - 1 - mantissa.leading_zeros()) as usize
+        } else {
+            self.m_zero_count += 1;
+            return;
+        };
+        self.m_histogram[bin] += 1;
+    }
+}
+
+impl Xsum for XsumProfile {
+    /// ```
+    /// use xsum::{Xsum, XsumProfile};
+    ///
+    /// let mut xprofile = XsumProfile::new();
+    /// assert_eq!(xprofile.sum(), -0.0);
+    /// ```
+    fn new() -> Self {
+        Self {
+            m_xsum: XsumAuto::new(),
+            m_abs_xsum: XsumAuto::new(),
+            m_histogram: vec![0; XSUM_PROFILE_BINS],
+            m_zero_count: 0,
+            m_subnormal_count: 0,
+            m_negative_count: 0,
+        }
+    }
+
+    /// ```
+    /// use xsum::{Xsum, XsumProfile};
+    ///
+    /// let mut xprofile = XsumProfile::new();
+    /// xprofile.add_list(&vec![1.0, -2.0, 3.0]);
+    /// assert_eq!(xprofile.sum(), 2.0);
+    /// assert_eq!(xprofile.abs_sum(), 6.0);
+    /// ```
+    fn add_list(&mut self, vec: &[f64]) {
+        self.m_xsum.add_list(vec);
+        for &value in vec {
+            self.m_abs_xsum.add(value.abs());
+            self.record(value);
+        }
+    }
+
+    /// ```
+    /// use xsum::{Xsum, XsumProfile};
+    ///
+    /// let mut xprofile = XsumProfile::new();
+    /// xprofile.add(-1.0);
+    /// assert_eq!(xprofile.sum(), -1.0);
+    /// assert_eq!(xprofile.negative_count(), 1);
+    /// ```
+    fn add(&mut self, value: f64) {
+        self.m_xsum.add(value);
+        self.m_abs_xsum.add(value.abs());
+        self.record(value);
+    }
+
+    /// ```
+    /// use xsum::{Xsum, XsumProfile};
+    ///
+    /// let mut xprofile = XsumProfile::new();
+    /// xprofile.add_list(&vec![0.1, 0.2, 0.3]);
+    /// assert_eq!(xprofile.sum(), 0.6);
+    /// ```
+    fn sum(&mut self) -> f64 {
+        self.m_xsum.sum()
+    }
+
+    /// ```
+    /// use xsum::{Xsum, XsumProfile};
+    ///
+    /// let mut xprofile = XsumProfile::new();
+    /// xprofile.add_list(&vec![1.0, 0.0]);
+    /// xprofile.clear();
+    /// assert_eq!(xprofile.zero_count(), 0);
+    /// assert_eq!(xprofile.max_exponent(), None);
+    /// ```
+    fn clear(&mut self) {
+        self.m_xsum.clear();
+        self.m_abs_xsum.clear();
+        self.m_histogram.fill(0);
+        self.m_zero_count = 0;
+        self.m_subnormal_count = 0;
+        self.m_negative_count = 0;
+    }
+}
@@ -1,66 +1,1106 @@
+use std::cmp::Ordering;
+
 use crate::{
-    accumulators::small_accumulator::SmallAccumulator,
+    accumulators::{
+        fixed_point::{
+            FixedPoint, HexFloat, Rounded, XSUM_EXACT_DECIMAL_PLACES, XSUM_FIXED_POINT_EXP,
+        },
+        small_accumulator::SmallAccumulator,
+    },
     constants::{
-        XSUM_EXP_BIAS, XSUM_EXP_MASK, XSUM_LOW_EXP_BITS, XSUM_LOW_MANTISSA_BITS,
-        XSUM_MANTISSA_BITS, XSUM_MANTISSA_MASK, XSUM_SIGN_MASK,
+        XSUM_BF16_MANTISSA_BITS, XSUM_EXP_BIAS, XSUM_EXP_MASK, XSUM_F16_MANTISSA_BITS,
+        XSUM_LOW_EXP_BITS, XSUM_LOW_MANTISSA_BITS, XSUM_MANTISSA_BITS, XSUM_MANTISSA_MASK,
+        XSUM_SIGN_MASK,
     },
-    Xsum,
+    error::XsumError,
+    flags::SumFlags,
+    parse_hex_error::ParseHexError,
+    policy::SpecialValuePolicy,
+    rounding_mode::RoundingMode,
+    sign::Sign,
+    ExactAddend, Xsum,
 };
 
-/// XsumSmall is efficient when vector or array size is less than or equal to 1,000
-///
-/// # Example
-///
-/// ```
-/// use xsum::{Xsum, XsumExt};
-///
-/// let vec = vec![1.0, 2.0, 3.0];
-/// assert_eq!(vec.xsum(), 6.0);
-/// ```
-#[cfg_attr(debug_assertions, derive(Debug))]
-pub struct XsumSmall {
-    m_sacc: SmallAccumulator,
-}
+/// XsumSmall is efficient when vector or array size is less than or equal to 1,000
+///
+/// # Example
+///
+/// ```
+/// use xsum::{Xsum, XsumExt};
+///
+/// let vec = vec![1.0, 2.0, 3.0];
+/// assert_eq!(vec.xsum(), 6.0);
+/// ```
+#[cfg_attr(debug_assertions, derive(Debug))]
+pub struct XsumSmall {
+    m_sacc: SmallAccumulator,
+}
+
+impl Default for XsumSmall {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl XsumSmall {
+    /// Creates an accumulator that handles NaN and infinite inputs according to `policy`
+    ///
+    /// ```
+    /// use xsum::{SpecialValuePolicy, Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::with_policy(SpecialValuePolicy::IgnoreNonFinite);
+    /// xsmall.add_list(&vec![1.0, f64::INFINITY, f64::NAN, 2.0]);
+    /// assert_eq!(xsmall.sum(), 3.0);
+    /// ```
+    #[must_use]
+    pub fn with_policy(policy: SpecialValuePolicy) -> Self {
+        Self {
+            m_sacc: SmallAccumulator::with_policy(policy),
+        }
+    }
+
+    /// Returns the policy for NaN and infinite inputs
+    ///
+    /// ```
+    /// use xsum::{SpecialValuePolicy, Xsum, XsumSmall};
+    ///
+    /// assert_eq!(XsumSmall::new().policy(), SpecialValuePolicy::Propagate);
+    /// ```
+    #[must_use]
+    pub const fn policy(&self) -> SpecialValuePolicy {
+        self.m_sacc.m_policy
+    }
+
+    /// Returns the same value as `sum()`, together with flags telling whether it had to be rounded
+    ///
+    /// ```
+    /// use xsum::{SumFlags, Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1.0, 2.0, 3.0]);
+    /// assert_eq!(xsmall.sum_with_flags(), (6.0, SumFlags::default()));
+    ///
+    /// xsmall.add(1e-300);
+    /// let (sum, flags) = xsmall.sum_with_flags();
+    /// assert_eq!(sum, 6.0);
+    /// assert!(flags.inexact);
+    ///
+    /// xsmall.add_list(&vec![f64::MAX, f64::MAX]);
+    /// let (sum, flags) = xsmall.sum_with_flags();
+    /// assert_eq!(sum, f64::INFINITY);
+    /// assert!(flags.overflow && flags.inexact);
+    /// ```
+    pub fn sum_with_flags(&mut self) -> (f64, SumFlags) {
+        let result: f64 = self.sum();
+        let mut flags = SumFlags::default();
+
+        if result.is_finite() {
+            // The rounding was exact if nothing is left after subtracting the result.
+            flags.inexact = !self.m_sacc.minus(result).is_zero();
+            flags.subnormal = result.is_subnormal();
+        } else if result.is_infinite()
+            && (self.m_sacc.m_inf == 0 || self.m_sacc.m_policy == SpecialValuePolicy::Error)
+        {
+            // Infinite without an infinite input in the sum: the exponent overflowed.
+            flags.overflow = true;
+            flags.inexact = true;
+        }
+        (result, flags)
+    }
+
+    /// Returns the exact sum rounded to 53 significant bits as `(mantissa, exponent)`,
+    /// like C's `frexp`, with an exponent that is not limited to the range of `f64`
+    ///
+    /// The mantissa is in `[0.5, 1)` (or `(-1, -0.5]`) and the rounded sum is
+    /// `mantissa * 2^exponent`. This stays finite when `sum()` overflows to infinity.
+    /// A zero, infinite or NaN sum is returned as the mantissa, with exponent 0.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![f64::MAX, f64::MAX, f64::MAX, f64::MAX]);
+    /// assert_eq!(xsmall.sum(), f64::INFINITY);
+    /// assert_eq!(xsmall.sum_extended(), (1.0 - f64::EPSILON / 2.0, 1026));
+    ///
+    /// xsmall.clear();
+    /// xsmall.add(-6.0);
+    /// assert_eq!(xsmall.sum_extended(), (-0.75, 3));
+    /// ```
+    pub fn sum_extended(&mut self) -> (f64, i64) {
+        if let Some(special) = self.m_sacc.special_value() {
+            return (special, 0);
+        }
+
+        let fixed_point: FixedPoint = self.m_sacc.to_fixed_point();
+        if fixed_point.is_zero() {
+            return (self.m_sacc.signed_zero(), 0);
+        }
+
+        // No limit on the exponent other than the lowest bit of the accumulator
+        let rounded: Rounded = fixed_point.round(XSUM_MANTISSA_BITS + 1, XSUM_FIXED_POINT_EXP);
+        let bits: i64 = 64 - i64::from(rounded.mantissa.leading_zeros());
+
+        // Put the mantissa bits under an exponent field for [0.5, 1)
+        let mut intv: i64 = (XSUM_EXP_BIAS - 1) << XSUM_MANTISSA_BITS;
+        intv |= ((rounded.mantissa << (XSUM_MANTISSA_BITS + 1 - bits)) as i64) & XSUM_MANTISSA_MASK;
+        if rounded.negative {
+            intv |= XSUM_SIGN_MASK;
+        }
+        (f64::from_bits(intv as u64), rounded.exp + bits)
+    }
+
+    /// Returns the exact sum rounded once to a binary format with `mantissa_bits` bits of
+    /// mantissa (not counting the implicit 1 bit) and exponents `min_exp` to `max_exp`
+    ///
+    /// The result is rounded to nearest, ties to even, to a normal or subnormal number of
+    /// that format, and is `f64` infinity if it overflows. The format must fit in `f64`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mantissa_bits` is more than 52, or if the exponents are not in order
+    /// within -1022 to 1023.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1.0, 2.0_f64.powi(-24), 2.0_f64.powi(-60)]);
+    /// // rounding the f64 sum to f32 would give 1.0, as 1 + 2^-24 is a tie
+    /// assert_eq!(xsmall.sum_to_precision(23, -126, 127), 1.0000001192092896);
+    /// assert_eq!(xsmall.sum_to_precision(2, -2, 2), 1.0);
+    ///
+    /// xsmall.add(7.0);
+    /// assert_eq!(xsmall.sum_to_precision(2, -2, 2), f64::INFINITY);
+    /// ```
+    pub fn sum_to_precision(&mut self, mantissa_bits: u32, min_exp: i32, max_exp: i32) -> f64 {
+        assert!(
+            i64::from(mantissa_bits) <= XSUM_MANTISSA_BITS,
+            "mantissa_bits must be at most 52"
+        );
+        assert!(
+            1 - XSUM_EXP_BIAS <= i64::from(min_exp)
+                && min_exp <= max_exp
+                && i64::from(max_exp) <= XSUM_EXP_BIAS,
+            "exponents must be in order within -1022 to 1023"
+        );
+
+        if let Some(special) = self.m_sacc.special_value() {
+            return special;
+        }
+
+        let fixed_point: FixedPoint = self.m_sacc.to_fixed_point();
+        if fixed_point.is_zero() {
+            return self.m_sacc.signed_zero();
+        }
+
+        // The lowest bit of the format is that of its smallest subnormal.
+        let mantissa_bits: i64 = i64::from(mantissa_bits);
+        let rounded: Rounded =
+            fixed_point.round(mantissa_bits + 1, i64::from(min_exp) - mantissa_bits);
+        if rounded.top_exp() > i64::from(max_exp) {
+            return if rounded.negative {
+                f64::NEG_INFINITY
+            } else {
+                f64::INFINITY
+            };
+        }
+        rounded.to_f64()
+    }
+
+    /// Returns the exact sum rounded once to `f32`
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1.0, 2.0_f64.powi(-24), 2.0_f64.powi(-60)]);
+    /// assert_eq!(xsmall.sum() as f32, 1.0);
+    /// assert_eq!(xsmall.sum_f32(), 1.0000001);
+    /// ```
+    pub fn sum_f32(&mut self) -> f32 {
+        // f32 holds the result exactly, or it is an infinity or NaN.
+        self.sum_to_precision(23, -126, 127) as f32
+    }
+
+    /// Returns the exact sum rounded once to binary16, as raw bits
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1.0, 2.0]);
+    /// assert_eq!(xsmall.sum_f16_bits(), 0x4200);
+    /// xsmall.add(1e5);
+    /// assert_eq!(xsmall.sum_f16_bits(), 0x7c00);
+    /// ```
+    pub fn sum_f16_bits(&mut self) -> u16 {
+        let sum: f64 = self.sum_to_precision(XSUM_F16_MANTISSA_BITS as u32, -14, 15);
+        SmallAccumulator::half_bits_of(sum, XSUM_F16_MANTISSA_BITS)
+    }
+
+    /// Returns the exact sum rounded once to bfloat16, as raw bits
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1.0, 2.0]);
+    /// assert_eq!(xsmall.sum_bf16_bits(), 0x4040);
+    /// xsmall.add(-1e-40);
+    /// assert_eq!(xsmall.sum_bf16_bits(), 0x4040);
+    /// ```
+    pub fn sum_bf16_bits(&mut self) -> u16 {
+        let sum: f64 = self.sum_to_precision(XSUM_BF16_MANTISSA_BITS as u32, -126, 127);
+        SmallAccumulator::half_bits_of(sum, XSUM_BF16_MANTISSA_BITS)
+    }
+
+    /// Returns the largest `f64` at or below the exact sum, and the smallest `f64` at or above it
+    ///
+    /// Both bounds come from one truncation of the exact sum, and they are equal when the sum
+    /// is exactly an `f64`. An infinite or NaN sum gives that value as both bounds. An exact
+    /// sum beyond the range of `f64` gives `f64::MAX` and infinity, or negative infinity and
+    /// `-f64::MAX`, so that the interval still holds it.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1.0, 1e-20]);
+    /// assert_eq!(xsmall.sum_interval(), (1.0, 1.0000000000000002));
+    ///
+    /// xsmall.add(-1e-20);
+    /// assert_eq!(xsmall.sum_interval(), (1.0, 1.0));
+    ///
+    /// xsmall.add_list(&vec![-1.0, -f64::MAX, -f64::MAX]);
+    /// assert_eq!(xsmall.sum_interval(), (f64::NEG_INFINITY, -f64::MAX));
+    /// ```
+    pub fn sum_interval(&mut self) -> (f64, f64) {
+        if let Some(special) = self.m_sacc.carried_special_value() {
+            return (special, special);
+        }
+
+        let fixed_point: FixedPoint = self.m_sacc.to_fixed_point();
+        if fixed_point.is_zero() {
+            let zero: f64 = self.m_sacc.signed_zero();
+            return (zero, zero);
+        }
+
+        // Truncating gives the bound nearer to zero, and if bits were lost, the next
+        // value away from zero is the other bound.
+        let precision: i64 = XSUM_MANTISSA_BITS + 1;
+        let (truncated, inexact) = fixed_point.truncate(precision, XSUM_FIXED_POINT_EXP + 1);
+        let sign: f64 = if truncated.negative { -1.0 } else { 1.0 };
+        let (toward_zero, away_from_zero): (f64, f64) = if truncated.top_exp() > XSUM_EXP_BIAS {
+            (sign * f64::MAX, sign * f64::INFINITY)
+        } else if !inexact {
+            let exact: f64 = truncated.to_f64();
+            (exact, exact)
+        } else {
+            let next: Rounded = truncated.next_away(precision);
+            if next.top_exp() > XSUM_EXP_BIAS {
+                (truncated.to_f64(), sign * f64::INFINITY)
+            } else {
+                (truncated.to_f64(), next.to_f64())
+            }
+        };
+
+        if truncated.negative {
+            (away_from_zero, toward_zero)
+        } else {
+            (toward_zero, away_from_zero)
+        }
+    }
+
+    /// Returns the exact sum as a nonoverlapping expansion, in increasing order of magnitude
+    ///
+    /// Each term is the sum of itself and the smaller terms, rounded to nearest, so the terms
+    /// are nonoverlapping in the sense of Shewchuk's robust arithmetic, and there are few of
+    /// them. A zero sum gives an empty expansion. An infinite or NaN sum, or an exact sum
+    /// beyond the range of `f64`, gives that single value instead.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1e100, 1.0, -1e-100, 1e100]);
+    /// assert_eq!(xsmall.to_expansion(), vec![-1e-100, 1.0, 2e100]);
+    /// ```
+    pub fn to_expansion(&mut self) -> Vec<f64> {
+        if let Some(special) = self.m_sacc.special_value() {
+            return vec![special];
+        }
+
+        let mut rest: Self = Self::new_with(&self.m_sacc);
+        let mut expansion: Vec<f64> = Vec::new();
+        loop {
+            // The sum is rounded to nearest, so what is left is at most half of its last bit.
+            let term: f64 = rest.sum();
+            if term == 0.0 {
+                break;
+            }
+            if term.is_infinite() {
+                return vec![term];
+            }
+            expansion.push(term);
+            rest.m_sacc = rest.m_sacc.minus(term);
+        }
+        expansion.reverse();
+        expansion
+    }
+
+    /// Adds the exact value of an expansion, a sequence of `f64` terms in any order
+    ///
+    /// This is the same as adding each term, and round-trips with `to_expansion()`.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_expansion(&[-1e-100, 1.0, 2e100]);
+    /// xsmall.add(-2e100);
+    /// assert_eq!(xsmall.to_expansion(), vec![-1e-100, 1.0]);
+    /// ```
+    pub fn add_expansion(&mut self, expansion: &[f64]) {
+        self.add_list(expansion);
+    }
+
+    /// Adds the exact value `hi + lo` of a double-double number
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_double_double(0.1, -5.551115123125783e-18);
+    /// xsmall.add(-0.1);
+    /// assert_eq!(xsmall.sum(), -5.551115123125783e-18);
+    /// ```
+    pub fn add_double_double(&mut self, hi: f64, lo: f64) {
+        self.add(hi);
+        self.add(lo);
+    }
+
+    /// Returns the exact accumulated value as a hexadecimal literal, in C99 `%a` style
+    ///
+    /// The literal has a leading `1` digit and as many hex digits after the point as the
+    /// value needs, so no bits are lost, even beyond the range or precision of `f64`. A zero
+    /// sum gives `0x0p+0` or `-0x0p+0`, and special values give `inf`, `-inf` or `nan`.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1.0, 0.5, 2.0_f64.powi(-60)]);
+    /// assert_eq!(xsmall.to_hex_string(), "0x1.800000000000001p+0");
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![f64::MAX, f64::MAX]);
+    /// assert_eq!(xsmall.to_hex_string(), "0x1.fffffffffffffp+1024");
+    /// ```
+    pub fn to_hex_string(&mut self) -> String {
+        if let Some(special) = self.m_sacc.carried_special_value() {
+            // The sign of a NaN carries no meaning.
+            return if special.is_nan() {
+                "nan".to_owned()
+            } else if special < 0.0 {
+                "-inf".to_owned()
+            } else {
+                "inf".to_owned()
+            };
+        }
+
+        let fixed_point: FixedPoint = self.m_sacc.to_fixed_point();
+        if fixed_point.is_zero() {
+            let sign: &str = if self.m_sacc.signed_zero().is_sign_negative() {
+                "-"
+            } else {
+                ""
+            };
+            return format!("{sign}0x0p+0");
+        }
+        fixed_point.to_hex()
+    }
+
+    /// Adds the exact value of a hexadecimal floating-point literal, such as `-0x1.8p+3`
+    ///
+    /// The literal has an optional sign, then `0x` or `0X`, hex digits with an optional point,
+    /// and an optional binary exponent after `p` or `P`. `inf`, `infinity` and `nan` are
+    /// also accepted in any case, with an optional sign. Nothing is added on error.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseHexError::Invalid`] if `s` is not such a literal,
+    /// [`ParseHexError::Inexact`] if the value has bits below 2^-1074, and
+    /// [`ParseHexError::OutOfRange`] if it is 2^1069 or more in magnitude.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_hex_str("0x1.800000000000001p+0").unwrap();
+    /// xsmall.add_hex_str("-0x1p-60").unwrap();
+    /// assert_eq!(xsmall.sum(), 1.5);
+    /// assert!(xsmall.add_hex_str("0x1p-1075").is_err());
+    /// ```
+    pub fn add_hex_str(&mut self, s: &str) -> Result<(), ParseHexError> {
+        match HexFloat::parse(s)? {
+            HexFloat::Finite(fixed_point) => self.m_sacc.add_fixed_point(&fixed_point),
+            HexFloat::Special(value) => self.add(value),
+        }
+        Ok(())
+    }
+
+    /// Returns the exact accumulated value as `(sign, magnitude, exponent)`, where the value is
+    /// the integer `magnitude` times 2^`exponent`
+    ///
+    /// The magnitude is in 64-bit limbs, lowest limb first, with no leading zero limbs, and it
+    /// is odd, so that each value has a single representation. A zero sum has no limbs and
+    /// exponent 0, with the sign of the zero that `sum()` returns.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same [`XsumError`] as `try_sum()` if a NaN or an infinity was added.
+    /// The exact value is never too large, so there is no [`XsumError::Overflow`].
+    ///
+    /// ```
+    /// use xsum::{Sign, Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1.5, 0.25]);
+    /// assert_eq!(xsmall.to_scaled_integer(), Ok((Sign::Positive, vec![7], -2)));
+    ///
+    /// xsmall.add_list(&vec![-1.75, -2.0_f64.powi(70), -2.0_f64.powi(6)]);
+    /// assert_eq!(xsmall.to_scaled_integer(), Ok((Sign::Negative, vec![1, 1], 6)));
+    /// ```
+    pub fn to_scaled_integer(&mut self) -> Result<(Sign, Vec<u64>, i64), XsumError> {
+        let fixed_point: FixedPoint = self.exact_value()?;
+        if fixed_point.is_zero() {
+            let sign: Sign = Sign::of_bits(self.m_sacc.signed_zero().to_bits() as i64);
+            return Ok((sign, Vec::new(), 0));
+        }
+
+        let sign: Sign = if fixed_point.negative {
+            Sign::Negative
+        } else {
+            Sign::Positive
+        };
+        let low: i64 = fixed_point.lowest_set_bit();
+        let odd: FixedPoint = fixed_point.shifted(-low);
+        let limbs: Vec<u64> = odd
+            .digits
+            .chunks(2)
+            .map(|pair| {
+                let high: u64 = pair.get(1).map_or(0, |&digit| u64::from(digit));
+                (high << XSUM_LOW_MANTISSA_BITS) | u64::from(pair[0])
+            })
+            .collect();
+        Ok((sign, limbs, low + XSUM_FIXED_POINT_EXP))
+    }
+
+    /// Returns the exact accumulated value divided by 2^`exp`, if that is an integer that
+    /// fits in `i128`
+    ///
+    /// Returns `None` if the quotient is not an integer or is out of range, or if a NaN or
+    /// an infinity was added.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![0.75, 1e20]);
+    /// assert_eq!(xsmall.to_i128_scaled(-2), Some(400_000_000_000_000_000_003));
+    /// assert_eq!(xsmall.to_i128_scaled(-1), None);
+    /// assert_eq!(xsmall.to_i128_scaled(-64), None);
+    /// ```
+    pub fn to_i128_scaled(&mut self, exp: i64) -> Option<i128> {
+        let (sign, limbs, exponent) = self.to_scaled_integer().ok()?;
+        if limbs.is_empty() {
+            return Some(0);
+        }
+
+        // The magnitude is odd, so the quotient is an integer only for a shift of 0 or more.
+        let shift: i64 = exponent.checked_sub(exp)?;
+        if limbs.len() > 2 || shift < 0 {
+            return None;
+        }
+        let magnitude: u128 =
+            u128::from(limbs[0]) | (u128::from(limbs.get(1).copied().unwrap_or(0)) << 64);
+        if shift > i64::from(magnitude.leading_zeros()) {
+            return None;
+        }
+        let magnitude: u128 = magnitude << shift;
+        match sign {
+            Sign::Positive => i128::try_from(magnitude).ok(),
+            Sign::Negative => 0_i128.checked_sub_unsigned(magnitude),
+        }
+    }
+
+    /// Creates an accumulator holding the integer `magnitude` times 2^`exp`, with `sign`
+    ///
+    /// The magnitude is in 64-bit limbs, lowest limb first, as `to_scaled_integer()` returns
+    /// it, but it need not be odd or trimmed. An empty or zero magnitude makes a zero sum
+    /// of that sign.
+    ///
+    /// Returns `None` if the value has bits below 2^-1074, or is 2^1069 or more in magnitude.
+    ///
+    /// ```
+    /// use xsum::{Sign, Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::from_scaled_integer(Sign::Negative, &[3], -1).unwrap();
+    /// assert_eq!(xsmall.sum(), -1.5);
+    /// xsmall.add(1.5);
+    /// assert_eq!(xsmall.to_scaled_integer(), Ok((Sign::Positive, vec![], 0)));
+    ///
+    /// assert!(XsumSmall::from_scaled_integer(Sign::Positive, &[1], -1075).is_none());
+    /// ```
+    #[must_use]
+    pub fn from_scaled_integer(sign: Sign, magnitude: &[u64], exp: i64) -> Option<Self> {
+        let fixed_point: FixedPoint = FixedPoint::from_limbs(sign, magnitude, exp)?;
+        let mut xsum_small: Self = Self::new();
+        xsum_small.m_sacc.add_fixed_point(&fixed_point);
+        Some(xsum_small)
+    }
+
+    /// Returns the exact sum rounded to an integer with `mode`
+    ///
+    /// The integer is read from the exact value, so it is right even where `sum() as i128`
+    /// would have lost the low digits.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same [`XsumError`] as `try_sum()` if a NaN or an infinity was added, and
+    /// [`XsumError::Overflow`] if the rounded sum does not fit in `i128`.
+    ///
+    /// ```
+    /// use xsum::{RoundingMode, Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1e20, 3.5]);
+    /// assert_eq!(xsmall.sum() as i128, 100_000_000_000_000_000_000);
+    /// assert_eq!(xsmall.round_i128(RoundingMode::HalfEven), Ok(100_000_000_000_000_000_004));
+    /// assert_eq!(xsmall.round_i128(RoundingMode::TowardZero), Ok(100_000_000_000_000_000_003));
+    ///
+    /// xsmall.add_list(&vec![-1e20, -7.0]);
+    /// assert_eq!(xsmall.round_i128(RoundingMode::HalfUp), Ok(-4));
+    /// assert_eq!(xsmall.round_i128(RoundingMode::Ceil), Ok(-3));
+    /// ```
+    pub fn round_i128(&mut self, mode: RoundingMode) -> Result<i128, XsumError> {
+        let fixed_point: FixedPoint = self.exact_value()?;
+        Self::signed_i128(fixed_point.negative, &fixed_point.round_to_integer(mode))
+    }
+
+    /// Returns the exact sum rounded toward negative infinity, as `round_i128(RoundingMode::Floor)`
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`XsumError`] as `round_i128()` does.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![-2.0, 2.0_f64.powi(-1000)]);
+    /// assert_eq!(xsmall.floor_i128(), Ok(-2));
+    /// ```
+    pub fn floor_i128(&mut self) -> Result<i128, XsumError> {
+        self.round_i128(RoundingMode::Floor)
+    }
+
+    /// Returns the exact sum rounded toward positive infinity, as `round_i128(RoundingMode::Ceil)`
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`XsumError`] as `round_i128()` does.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![-2.0, 2.0_f64.powi(-1000)]);
+    /// assert_eq!(xsmall.ceil_i128(), Ok(-1));
+    /// ```
+    pub fn ceil_i128(&mut self) -> Result<i128, XsumError> {
+        self.round_i128(RoundingMode::Ceil)
+    }
+
+    /// Returns the integer part of the exact sum, as `round_i128(RoundingMode::TowardZero)`
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`XsumError`] as `round_i128()` does.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![-2.0, 2.0_f64.powi(-1000)]);
+    /// assert_eq!(xsmall.trunc_i128(), Ok(-1));
+    /// ```
+    pub fn trunc_i128(&mut self) -> Result<i128, XsumError> {
+        self.round_i128(RoundingMode::TowardZero)
+    }
+
+    /// Returns the fractional part of the exact sum, rounded once to `f64`
+    ///
+    /// Like `f64::fract()`, the fractional part has the sign of the sum, and is zero with that
+    /// sign when the sum is an integer. A fractional part within half an ulp of 1 rounds to
+    /// `1.0` (or `-1.0`). An infinite or NaN sum gives NaN.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1e20, 0.25]);
+    /// assert_eq!(xsmall.sum().fract(), 0.0);
+    /// assert_eq!(xsmall.fract(), 0.25);
+    ///
+    /// xsmall.add_list(&vec![-1e20, -1.5]);
+    /// assert_eq!(xsmall.fract(), -0.25);
+    /// ```
+    pub fn fract(&mut self) -> f64 {
+        if let Some(special) = self.m_sacc.carried_special_value() {
+            return if special.is_nan() { special } else { f64::NAN };
+        }
+
+        let fixed_point: FixedPoint = self.m_sacc.to_fixed_point();
+        if fixed_point.is_zero() {
+            return self.m_sacc.signed_zero();
+        }
+        let fraction: FixedPoint = fixed_point.fract();
+        if fraction.is_zero() {
+            return if fixed_point.negative { -0.0 } else { 0.0 };
+        }
+        fraction
+            .round(XSUM_MANTISSA_BITS + 1, XSUM_FIXED_POINT_EXP + 1)
+            .to_f64()
+    }
+
+    /// Returns the exact sum rounded once to `places` decimal places with `mode`, as
+    /// `(mantissa, scale)`, where the rounded sum is `mantissa / 10^scale` and `scale` is `places`
+    ///
+    /// Formatting `sum()` with `{:.2}` rounds twice, first to binary and then to decimal,
+    /// which can be wrong at the ties that accounting rules care about. This rounds once.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same [`XsumError`] as `try_sum()` if a NaN or an infinity was added, and
+    /// [`XsumError::Overflow`] if the mantissa does not fit in `i128`.
+    ///
+    /// ```
+    /// use xsum::{RoundingMode, Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1.0, 0.125, 2.0_f64.powi(-60)]);
+    /// assert_eq!(format!("{:.2}", xsmall.sum()), "1.12");
+    /// assert_eq!(xsmall.to_decimal_rounded(2, RoundingMode::HalfEven), Ok((113, 2)));
+    ///
+    /// xsmall.add(-(2.0_f64.powi(-60)));
+    /// assert_eq!(xsmall.to_decimal_rounded(2, RoundingMode::HalfEven), Ok((112, 2)));
+    /// assert_eq!(xsmall.to_decimal_rounded(2, RoundingMode::HalfUp), Ok((113, 2)));
+    /// ```
+    pub fn to_decimal_rounded(
+        &mut self,
+        places: u32,
+        mode: RoundingMode,
+    ) -> Result<(i128, u32), XsumError> {
+        let fixed_point: FixedPoint = self.exact_value()?;
+        let exact_places: u32 = places.min(XSUM_EXACT_DECIMAL_PLACES);
+        let integer: Vec<u32> = fixed_point
+            .scaled_by_pow10(exact_places)
+            .round_to_integer(mode);
+
+        // At that many places, any value other than zero is far beyond i128.
+        if places > exact_places && !integer.is_empty() {
+            let sign: Sign = if fixed_point.negative {
+                Sign::Negative
+            } else {
+                Sign::Positive
+            };
+            return Err(XsumError::Overflow { sign });
+        }
+        Self::signed_i128(fixed_point.negative, &integer).map(|mantissa| (mantissa, places))
+    }
+
+    /// Returns the exact sum rounded once to `places` decimal places with `mode`, as a string
+    ///
+    /// The string has exactly `places` digits after the point, and no point if `places` is 0.
+    /// It has no exponent and no length limit, and a sum that rounds to zero has no minus sign.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same [`XsumError`] as `try_sum()` if a NaN or an infinity was added.
+    ///
+    /// ```
+    /// use xsum::{RoundingMode, Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![-2.675, -1e-3]);
+    /// assert_eq!(xsmall.to_decimal_string(2, RoundingMode::HalfEven).unwrap(), "-2.68");
+    /// assert_eq!(xsmall.to_decimal_string(0, RoundingMode::Floor).unwrap(), "-3");
+    ///
+    /// xsmall.add_list(&vec![2.675, 1e-3, 2e30]);
+    /// assert_eq!(
+    ///     xsmall.to_decimal_string(3, RoundingMode::HalfUp).unwrap(),
+    ///     "2000000000000000039769249677312.000"
+    /// );
+    /// ```
+    pub fn to_decimal_string(
+        &mut self,
+        places: u32,
+        mode: RoundingMode,
+    ) -> Result<String, XsumError> {
+        let fixed_point: FixedPoint = self.exact_value()?;
+        let exact_places: u32 = places.min(XSUM_EXACT_DECIMAL_PLACES);
+        let integer: Vec<u32> = fixed_point
+            .scaled_by_pow10(exact_places)
+            .round_to_integer(mode);
+
+        // Pad the digits so that there is at least one before the point.
+        let digits: String = FixedPoint::integer_to_decimal(&integer);
+        let width: usize = exact_places as usize + 1;
+        let digits: String = format!("{digits:0>width$}");
+        let (whole, fraction) = digits.split_at(digits.len() - exact_places as usize);
+
+        let mut decimal = String::new();
+        if fixed_point.negative && !integer.is_empty() {
+            decimal.push('-');
+        }
+        decimal.push_str(whole);
+        if places > 0 {
+            decimal.push('.');
+            decimal.push_str(fraction);
+            // Any places beyond those are zeros.
+            decimal.extend(std::iter::repeat('0').take((places - exact_places) as usize));
+        }
+        Ok(decimal)
+    }
+
+    /// Compares the exact sum with `x`, or returns `None` if either is NaN
+    ///
+    /// Unlike comparing `sum()` with `x`, this is not fooled by the rounding of the sum.
+    /// An infinity compares as `sum()` would.
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1.0, 2.0_f64.powi(-60)]);
+    /// assert_eq!(xsmall.sum(), 1.0);
+    /// assert_eq!(xsmall.cmp_f64(1.0), Some(Ordering::Greater));
+    /// assert_eq!(xsmall.cmp_f64(f64::INFINITY), Some(Ordering::Less));
+    /// assert_eq!(xsmall.cmp_f64(f64::NAN), None);
+    /// ```
+    #[must_use]
+    pub fn cmp_f64(&self, x: f64) -> Option<Ordering> {
+        self.m_sacc.cmp_f64(x)
+    }
+
+    /// Returns the sign of the exact sum: `1.0`, `-1.0`, or `0.0` if it is exactly zero
+    ///
+    /// Unlike `f64::signum`, a zero sum gives `0.0`. An infinity gives its sign, and a NaN
+    /// gives NaN. Only carries are propagated, so this is cheaper than `sum()`.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1e-300, 1e300, -1e300]);
+    /// assert_eq!(xsmall.signum(), 1.0);
+    ///
+    /// xsmall.add(-1e-300);
+    /// assert_eq!(xsmall.signum(), 0.0);
+    ///
+    /// xsmall.add(f64::NEG_INFINITY);
+    /// assert_eq!(xsmall.signum(), -1.0);
+    /// ```
+    pub fn signum(&mut self) -> f64 {
+        self.m_sacc.value_signum()
+    }
+
+    /// Returns `true` if the exact sum is zero, of either sign
+    ///
+    /// A sum with a NaN or an infinity is not zero. Only carries are propagated, so this
+    /// is cheaper than `sum()`.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// assert!(xsmall.is_zero());
+    ///
+    /// xsmall.add_list(&vec![0.1, 0.2, -0.3]);
+    /// assert_eq!(0.1 + 0.2 - 0.3, 5.551115123125783e-17);
+    /// assert!(!xsmall.is_zero());
+    ///
+    /// xsmall.add_list(&vec![-0.1, -0.2, 0.3]);
+    /// assert!(xsmall.is_zero());
+    /// ```
+    pub fn is_zero(&mut self) -> bool {
+        self.signum() == 0.0
+    }
+
+    /// Returns the exact sum minus `candidate`, rounded once to the nearest `f64`
+    ///
+    /// Subtracting `sum()` from a candidate rounds twice, and misses errors smaller than half
+    /// an ulp of the sum. The difference is `0.0` only if the candidate is exactly the sum.
+    /// With an infinite or NaN sum or candidate, this is what IEEE subtraction would give.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let vec = vec![0.1, 0.2, 0.3];
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec);
+    /// let naive: f64 = vec.iter().sum();
+    /// assert_eq!(naive - xsmall.sum(), 1.1102230246251565e-16);
+    /// assert_eq!(xsmall.error_of(naive), -8.326672684688674e-17);
+    /// assert_eq!(xsmall.error_of(0.6), 2.7755575615628914e-17);
+    /// ```
+    pub fn error_of(&mut self, candidate: f64) -> f64 {
+        if let Some(special) = self.m_sacc.carried_special_value() {
+            return special - candidate;
+        }
+        if !candidate.is_finite() {
+            return if candidate.is_nan() {
+                candidate
+            } else {
+                -candidate
+            };
+        }
+
+        let mut difference = Self {
+            m_sacc: self.m_sacc.minus(candidate),
+        };
+        if difference.is_zero() {
+            return 0.0;
+        }
+        difference.sum()
+    }
+
+    /// Returns how far `candidate` is from the exact sum, in units in the last place of
+    /// `candidate`, rounded down
+    ///
+    /// The distance is found exactly from the accumulator, so `0` means the candidate is
+    /// within one ulp of the sum, which holds for the correctly rounded `sum()`. The count
+    /// saturates at `u64::MAX`, which is also returned for a NaN, or for an infinity that
+    /// is not the sum.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1.0, 2.0_f64.powi(-52), 2.0_f64.powi(-60)]);
+    /// let sum = xsmall.sum();
+    /// assert_eq!(sum, 1.0000000000000002);
+    /// assert_eq!(xsmall.ulps_from(sum), 0);
+    /// assert_eq!(xsmall.ulps_from(1.0), 1);
+    /// assert_eq!(xsmall.ulps_from(1.0000000000000009), 2);
+    /// assert_eq!(xsmall.ulps_from(f64::NAN), u64::MAX);
+    /// ```
+    pub fn ulps_from(&mut self, candidate: f64) -> u64 {
+        if let Some(special) = self.m_sacc.carried_special_value() {
+            return if special == candidate { 0 } else { u64::MAX };
+        }
+        if !candidate.is_finite() {
+            return u64::MAX;
+        }
+        self.m_sacc.ulps_from(candidate)
+    }
+
+    /// Calls `f` with a small accumulator holding the exact value, for comparisons and hashing
+    pub(crate) fn with_exact<R>(&self, f: impl FnOnce(&SmallAccumulator) -> R) -> R {
+        f(&self.m_sacc)
+    }
+
+    /// Multiplies the accumulated value by 2^`k`
+    ///
+    /// This is exact, including for values beyond the range of `f64`, unless bits would fall
+    /// below the smallest subnormal. In that case only those bits are rounded, to the nearest
+    /// multiple of 2^-1074 with ties to even, and every bit above them is kept. A value too
+    /// large even for the accumulator is an overflow:
+    /// `sum()` gives an infinity of its sign and `try_sum()` gives [`XsumError::Overflow`].
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![0.1, 0.2]);
+    /// xsmall.scale_pow2(-3);
+    /// assert_eq!(xsmall.sum(), (0.1 + 0.2) / 8.0);
+    ///
+    /// xsmall.add(f64::MAX);
+    /// xsmall.scale_pow2(2);
+    /// assert_eq!(xsmall.sum(), f64::INFINITY);
+    /// xsmall.scale_pow2(-2);
+    /// assert_eq!(xsmall.sum(), f64::MAX);
+    /// ```
+    pub fn scale_pow2(&mut self, k: i32) {
+        self.m_sacc.scale_pow2(i64::from(k));
+    }
+
+    /// Multiplies the accumulated value by `n` exactly
+    ///
+    /// A value too large for the accumulator is an overflow, as for `scale_pow2()`.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![0.1, 0.25]);
+    /// xsmall.mul_int(-3);
+    /// xsmall.add(0.3);
+    /// assert_eq!(xsmall.sum(), -0.75);
+    /// ```
+    pub fn mul_int(&mut self, n: i64) {
+        self.m_sacc.mul_int(n);
+    }
+
+    /// Adds `value` `count` times, exactly and in constant time
+    ///
+    /// The result is the same as calling `add(value)` `count` times.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_repeated(0.1, 3_000_000);
+    /// assert_eq!(xsmall.sum(), 300_000.0);
+    /// assert_eq!(xsmall.len(), 3_000_000);
+    /// ```
+    pub fn add_repeated(&mut self, value: f64, count: u64) {
+        self.m_sacc.add_repeated(value, count);
+    }
+
+    /// Adds each value of `(value, count)` pairs `count` times, as `add_repeated` does
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_repeated_list(&[(1e16, 1_000), (1.0, 1_000), (-1e16, 1_000)]);
+    /// assert_eq!(xsmall.sum(), 1_000.0);
+    /// ```
+    pub fn add_repeated_list(&mut self, pairs: &[(f64, u64)]) {
+        for &(value, count) in pairs {
+            self.m_sacc.add_repeated(value, count);
+        }
+    }
 
-impl Default for XsumSmall {
-    fn default() -> Self {
-        Self::new()
+    /// Adds the integer `value` exactly, without rounding it to `f64` first
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_i64(i64::MAX);
+    /// xsmall.add(-9.223372036854775e18);
+    /// assert_eq!(xsmall.sum(), 1_023.0);
+    /// ```
+    pub fn add_i64(&mut self, value: i64) {
+        self.m_sacc
+            .add_integer(value < 0, value.unsigned_abs().into());
     }
-}
 
-impl XsumSmall {
-    pub(crate) fn new_with(small_accumulator: &SmallAccumulator) -> Self {
-        Self {
-            m_sacc: SmallAccumulator::new_based_on(small_accumulator),
+    /// Adds the integers of `vec` exactly, as `add_i64` does
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_i64_list(&[9_007_199_254_740_993, 1, -2]);
+    /// assert_eq!(xsmall.sum(), 9_007_199_254_740_992.0);
+    /// ```
+    pub fn add_i64_list(&mut self, vec: &[i64]) {
+        for &value in vec {
+            self.add_i64(value);
         }
     }
 
-    #[inline(always)]
-    pub(crate) const fn get_size_count(&self) -> usize {
-        self.m_sacc.m_size_count
+    /// Adds the integer `value` exactly, without rounding it to `f64` first
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_u64(u64::MAX);
+    /// xsmall.add(-1.8446744073709552e19);
+    /// assert_eq!(xsmall.sum(), -1.0);
+    /// ```
+    pub fn add_u64(&mut self, value: u64) {
+        self.m_sacc.add_integer(false, value.into());
     }
 
-    #[inline(always)]
-    pub(crate) fn transfer_accumulator(self) -> SmallAccumulator {
-        self.m_sacc
+    /// Adds the integers of `vec` exactly, as `add_u64` does
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_u64_list(&[u64::MAX, 1]);
+    /// assert_eq!(xsmall.sum(), 18_446_744_073_709_551_616.0);
+    /// ```
+    pub fn add_u64_list(&mut self, vec: &[u64]) {
+        for &value in vec {
+            self.add_u64(value);
+        }
     }
-}
 
-impl Xsum for XsumSmall {
-    fn new() -> Self {
-        Self {
-            m_sacc: SmallAccumulator::new(),
+    /// Adds the integer `value` exactly, without rounding it to `f64` first
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_i128(i128::MIN);
+    /// xsmall.add(1.7014118346046923e38);
+    /// assert_eq!(xsmall.sum(), 0.0);
+    /// ```
+    pub fn add_i128(&mut self, value: i128) {
+        self.m_sacc.add_integer(value < 0, value.unsigned_abs());
+    }
+
+    /// Adds the integers of `vec` exactly, as `add_i128` does
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_i128_list(&[1 << 100, 3, -(1 << 100)]);
+    /// assert_eq!(xsmall.sum(), 3.0);
+    /// ```
+    pub fn add_i128_list(&mut self, vec: &[i128]) {
+        for &value in vec {
+            self.add_i128(value);
         }
     }
 
+    /// Adds a number of any type that converts to `f64` exactly
+    ///
     /// ```
     /// use xsum::{Xsum, XsumSmall};
     ///
     /// let mut xsmall = XsumSmall::new();
-    /// xsmall.add_list(&vec![1.0, 2.0, 3.0]);
-    /// assert_eq!(xsmall.sum(), 6.0);
+    /// xsmall.add_generic(0.1f32);
+    /// xsmall.add_generic(-1i32);
+    /// assert_eq!(xsmall.sum(), -0.8999999985098839);
     /// ```
-    fn add_list(&mut self, vec: &[f64]) {
+    #[inline(always)]
+    pub fn add_generic<T: ExactAddend>(&mut self, value: T) {
+        self.add(value.to_f64());
+    }
+
+    /// Adds a slice of numbers of any type that converts to `f64` exactly
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_slice(&[16_777_217u32, 1]);
+    /// xsmall.add_slice(&[0.5f32]);
+    /// assert_eq!(xsmall.sum(), 16_777_218.5);
+    /// ```
+    pub fn add_slice<T: ExactAddend>(&mut self, vec: &[T]) {
         let mut offset: usize = 0;
         let mut n: usize = vec.len();
 
@@ -70,6 +1110,7 @@ impl Xsum for XsumSmall {
             }
             let m: usize = std::cmp::min(n, self.m_sacc.m_adds_until_propagate as usize);
             for &value in &vec[offset..offset + m] {
+                let value: f64 = value.to_f64();
                 self.m_sacc.increment_when_value_added(value);
                 self.m_sacc.add1_no_carry(value);
             }
@@ -79,6 +1120,268 @@ impl Xsum for XsumSmall {
         }
     }
 
+    /// Adds binary16 (IEEE half precision) numbers given as raw bits, exactly
+    ///
+    /// Subnormals, infinities and NaN payloads are decoded as for `f64` inputs.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// // 1.0, 0.1 rounded to binary16, and the smallest binary16 subnormal
+    /// xsmall.add_f16_bits(&[0x3c00, 0x2e66, 0x0001]);
+    /// assert_eq!(xsmall.sum(), 1.0999756455421448);
+    /// ```
+    pub fn add_f16_bits(&mut self, bits: &[u16]) {
+        for &value in bits {
+            self.m_sacc.add_half_bits(value, XSUM_F16_MANTISSA_BITS);
+        }
+    }
+
+    /// Adds bfloat16 numbers given as raw bits, exactly
+    ///
+    /// Subnormals, infinities and NaN payloads are decoded as for `f64` inputs.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// // 1.0, -0.1 rounded to bfloat16, and -Inf
+    /// xsmall.add_bf16_bits(&[0x3f80, 0xbdcd]);
+    /// assert_eq!(xsmall.sum(), 0.89990234375);
+    /// xsmall.add_bf16_bits(&[0xff80]);
+    /// assert_eq!(xsmall.sum(), f64::NEG_INFINITY);
+    /// ```
+    pub fn add_bf16_bits(&mut self, bits: &[u16]) {
+        for &value in bits {
+            self.m_sacc.add_half_bits(value, XSUM_BF16_MANTISSA_BITS);
+        }
+    }
+
+    pub(crate) fn new_with(small_accumulator: &SmallAccumulator) -> Self {
+        Self {
+            m_sacc: SmallAccumulator::new_based_on(small_accumulator),
+        }
+    }
+
+    /// Returns the number of values added so far
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1.0, -1.0, f64::NAN]);
+    /// assert_eq!(xsmall.len(), 3);
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.m_sacc.m_size_count
+    }
+
+    /// Returns `true` if no value has been added
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// assert!(xsmall.is_empty());
+    /// xsmall.add(0.0);
+    /// assert!(!xsmall.is_empty());
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.m_sacc.m_size_count == 0
+    }
+
+    /// Returns `true` if a NaN has been added
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1.0, f64::NAN]);
+    /// assert!(xsmall.has_nan());
+    /// ```
+    #[must_use]
+    pub const fn has_nan(&self) -> bool {
+        self.m_sacc.m_nan != 0
+    }
+
+    /// Returns `true` if `+Inf` or `-Inf` has been added
+    ///
+    /// This is also `true` when both `+Inf` and `-Inf` were added, although `sum()` is then NaN.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![f64::INFINITY, f64::NEG_INFINITY]);
+    /// assert!(xsmall.has_infinity());
+    /// assert!(xsmall.sum().is_nan());
+    /// ```
+    #[must_use]
+    pub const fn has_infinity(&self) -> bool {
+        self.m_sacc.m_inf != 0
+    }
+
+    /// Returns the 52-bit mantissa field (quiet bit included) of the NaN that `sum()` would return,
+    /// or `None` if no NaN has been added
+    ///
+    /// When several NaNs are added, the one with the largest payload is kept.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// assert_eq!(xsmall.nan_payload(), None);
+    /// xsmall.add(f64::from_bits(0x7ff8_0000_0000_002a));
+    /// assert_eq!(xsmall.nan_payload(), Some(0x8_0000_0000_002a));
+    /// ```
+    #[must_use]
+    pub const fn nan_payload(&self) -> Option<u64> {
+        if self.m_sacc.m_nan == 0 {
+            None
+        } else {
+            Some((self.m_sacc.m_nan & XSUM_MANTISSA_MASK) as u64)
+        }
+    }
+
+    /// Returns a human-readable listing of the non-zero chunks of the accumulator
+    /// together with the range of binary exponents each one covers
+    ///
+    /// The format is meant for debugging and may change between versions.
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add(1.0);
+    /// let dump = xsmall.debug_dump();
+    /// assert!(dump.contains("chunk[32]"));
+    /// ```
+    #[must_use]
+    pub fn debug_dump(&self) -> String {
+        let mut out = String::new();
+        self.m_sacc.write_dump(&mut out);
+        out
+    }
+
+    /// Returns the same value as `sum()` if it is finite, otherwise the reason why it is not
+    ///
+    /// Unlike `sum()`, this distinguishes an infinite input from an exact sum of finite
+    /// inputs that is too large for `f64`.
+    ///
+    /// ```
+    /// use xsum::{Sign, Xsum, XsumError, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1.0, 2.0, 3.0]);
+    /// assert_eq!(xsmall.try_sum(), Ok(6.0));
+    ///
+    /// xsmall.add(f64::NEG_INFINITY);
+    /// assert_eq!(xsmall.try_sum(), Err(XsumError::InfiniteInput { sign: Sign::Negative }));
+    ///
+    /// xsmall.clear();
+    /// xsmall.add_list(&vec![-f64::MAX, -f64::MAX]);
+    /// assert_eq!(xsmall.try_sum(), Err(XsumError::Overflow { sign: Sign::Negative }));
+    /// ```
+    pub fn try_sum(&mut self) -> Result<f64, XsumError> {
+        if let Some(error) = self.special_error() {
+            return Err(error);
+        }
+
+        // With only finite inputs, an infinite result comes from the exponent overflow in sum()
+        let result: f64 = self.sum();
+        if result.is_infinite() {
+            return Err(XsumError::Overflow {
+                sign: Sign::of_bits(result.to_bits() as i64),
+            });
+        }
+        Ok(result)
+    }
+
+    /// Returns the exact value, or why the sum is not finite as for `try_sum()`
+    fn exact_value(&mut self) -> Result<FixedPoint, XsumError> {
+        // The policy may keep a NaN or an infinity out of the value, but not out of the error.
+        self.m_sacc.carried_special_value();
+        match self.special_error() {
+            Some(error) => Err(error),
+            None => Ok(self.m_sacc.to_fixed_point()),
+        }
+    }
+
+    /// Returns the integer from `FixedPoint::round_to_integer`, negated if `negative`,
+    /// or an overflow error if it does not fit in `i128`
+    fn signed_i128(negative: bool, integer: &[u32]) -> Result<i128, XsumError> {
+        let magnitude: Option<u128> = FixedPoint::integer_to_u128(integer);
+        if negative {
+            magnitude
+                .and_then(|m| 0_i128.checked_sub_unsigned(m))
+                .ok_or(XsumError::Overflow {
+                    sign: Sign::Negative,
+                })
+        } else {
+            magnitude
+                .and_then(|m| i128::try_from(m).ok())
+                .ok_or(XsumError::Overflow {
+                    sign: Sign::Positive,
+                })
+        }
+    }
+
+    /// Returns why the sum is not finite if a NaN, an infinity or an overflow of the chunks
+    /// was recorded, whatever the policy
+    const fn special_error(&self) -> Option<XsumError> {
+        if self.m_sacc.m_nan != 0 {
+            return Some(XsumError::NaNInput {
+                payload: (self.m_sacc.m_nan & XSUM_MANTISSA_MASK) as u64,
+            });
+        }
+
+        if self.m_sacc.m_inf != 0 {
+            // +Inf and -Inf together are recorded as a NaN
+            if self.m_sacc.m_inf & XSUM_MANTISSA_MASK != 0 {
+                return Some(XsumError::OppositeInfinities);
+            }
+            return Some(XsumError::InfiniteInput {
+                sign: Sign::of_bits(self.m_sacc.m_inf),
+            });
+        }
+
+        if self.m_sacc.m_overflow != 0 {
+            return Some(XsumError::Overflow {
+                sign: Sign::of_bits(self.m_sacc.m_overflow),
+            });
+        }
+        None
+    }
+
+    #[inline(always)]
+    pub(crate) fn transfer_accumulator(self) -> SmallAccumulator {
+        self.m_sacc
+    }
+}
+
+impl Xsum for XsumSmall {
+    fn new() -> Self {
+        Self {
+            m_sacc: SmallAccumulator::new(),
+        }
+    }
+
+    /// ```
+    /// use xsum::{Xsum, XsumSmall};
+    ///
+    /// let mut xsmall = XsumSmall::new();
+    /// xsmall.add_list(&vec![1.0, 2.0, 3.0]);
+    /// assert_eq!(xsmall.sum(), 6.0);
+    /// ```
+    fn add_list(&mut self, vec: &[f64]) {
+        self.add_slice(vec);
+    }
+
     /// ```
     /// use xsum::{Xsum, XsumSmall};
     ///
@@ -91,12 +1394,7 @@ impl Xsum for XsumSmall {
     /// ```
     #[inline(always)]
     fn add(&mut self, value: f64) {
-        self.m_sacc.increment_when_value_added(value);
-        if self.m_sacc.m_adds_until_propagate == 0 {
-            self.m_sacc.carry_propagate();
-        }
-        self.m_sacc.add1_no_carry(value);
-        self.m_sacc.m_adds_until_propagate -= 1;
+        self.m_sacc.add_value(value);
     }
 
     /// ```
@@ -114,12 +1412,10 @@ impl Xsum for XsumSmall {
         // and a sum of other numbers that overflows with opposite sign,
         // since there is no real ambiguity regarding the sign in such a case.
 
-        if self.m_sacc.m_nan != 0 {
-            return f64::from_bits(self.m_sacc.m_nan as u64);
-        }
+        // With SpecialValuePolicy::Error, NaN and Inf are only recorded for try_sum().
 
-        if self.m_sacc.m_inf != 0 {
-            return f64::from_bits(self.m_sacc.m_inf as u64);
+        if let Some(special) = self.m_sacc.special_value() {
+            return special;
         }
 
         if self.m_sacc.m_size_count == 0 {
@@ -137,6 +1433,12 @@ impl Xsum for XsumSmall {
         // i is 0 (the lowest chunk), in which case it will be handled by
         // the code for denormalized numbers.
         let i: i32 = self.m_sacc.carry_propagate();
+
+        // A carry out of the top chunk, from a sum too large for the accumulator, is an overflow.
+        if let Some(special) = self.m_sacc.special_value() {
+            return special;
+        }
+
         let mut ivalue: i64 = self.m_sacc.m_chunk[i as usize];
         let mut intv: i64;
 
@@ -144,11 +1446,7 @@ impl Xsum for XsumSmall {
         if i <= 1 {
             // Check for zero value, in which case we can return immediately.
             if ivalue == 0 {
-                return if !self.m_sacc.m_has_pos_number {
-                    -0.0
-                } else {
-                    0.0
-                };
+                return self.m_sacc.signed_zero();
             }
 
             // Check if it is actually a denormalized number.  It always is if only
@@ -288,22 +1586,24 @@ impl Xsum for XsumSmall {
             if (ivalue & 3) == 3 {
                 // extra bits are 11
                 should_round_away_from_zero = true;
-            }
-
-            if lower == 0 {
-                // see if any lower bits are non-zero
-                while j > 0 {
-                    j -= 1;
-                    if self.m_sacc.m_chunk[j as usize] != 0 {
-                        lower = 1;
-                        break;
+            } else if (ivalue & 3) == 2 && (ivalue & 4) != 0 {
+                // low bit 1 (odd), extra bits are 10; otherwise extra bits are 0x,
+                // or 10 and even, and just discarding lower order bits is correct
+                if lower == 0 {
+                    // see if any lower bits are non-zero
+                    while j > 0 {
+                        j -= 1;
+                        if self.m_sacc.m_chunk[j as usize] != 0 {
+                            lower = 1;
+                            break;
+                        }
                     }
                 }
-            }
 
-            if lower == 0 {
-                // low bit 1 (odd), extra bits are 10, lower bits are all 0
-                should_round_away_from_zero = true;
+                if lower == 0 {
+                    // low bit 1 (odd), extra bits are 10, lower bits are all 0
+                    should_round_away_from_zero = true;
+                }
             }
         }
 
@@ -337,6 +1637,8 @@ impl Xsum for XsumSmall {
         f64::from_bits(intv as u64)
     }
 
+    /// Resets the sum, keeping the policy for NaN and infinite inputs
+    ///
     /// ```
     /// use xsum::{Xsum, XsumSmall};
     ///
@@ -351,6 +1653,6 @@ impl Xsum for XsumSmall {
     /// ```
     #[inline(always)]
     fn clear(&mut self) {
-        *self = Self::default();
+        *self = Self::with_policy(self.policy());
     }
 }
@@ -1,4 +1,4 @@
-use crate::{Xsum, XsumAuto, XsumLarge, XsumSmall};
+use crate::{ExactAddend, Xsum, XsumAuto, XsumLarge, XsumSmall};
 
 /// `XsumVariant` provides an easy way to manage multiple xsum variants.
 ///
@@ -30,6 +30,44 @@ pub enum XsumVariant {
     Auto(XsumAuto),
 }
 
+impl XsumVariant {
+    /// Adds a number of any type that converts to `f64` exactly
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumLarge, XsumVariant};
+    ///
+    /// let mut xvariant = XsumVariant::Large(XsumLarge::new());
+    /// xvariant.add_generic(0.1f32);
+    /// xvariant.add_generic(-1i32);
+    /// assert_eq!(xvariant.sum(), -0.8999999985098839);
+    /// ```
+    #[inline(always)]
+    pub fn add_generic<T: ExactAddend>(&mut self, value: T) {
+        match self {
+            Self::Small(xsum_small) => xsum_small.add_generic(value),
+            Self::Large(xsum_large) => xsum_large.add_generic(value),
+            Self::Auto(xsum_auto) => xsum_auto.add_generic(value),
+        }
+    }
+
+    /// Adds a slice of numbers of any type that converts to `f64` exactly
+    ///
+    /// ```
+    /// use xsum::{Xsum, XsumAuto, XsumVariant};
+    ///
+    /// let mut xvariant = XsumVariant::Auto(XsumAuto::new());
+    /// xvariant.add_slice(&[u8::MAX; 2_000]);
+    /// assert_eq!(xvariant.sum(), 510_000.0);
+    /// ```
+    pub fn add_slice<T: ExactAddend>(&mut self, vec: &[T]) {
+        match self {
+            Self::Small(xsum_small) => xsum_small.add_slice(vec),
+            Self::Large(xsum_large) => xsum_large.add_slice(vec),
+            Self::Auto(xsum_auto) => xsum_auto.add_slice(vec),
+        }
+    }
+}
+
 impl Xsum for XsumVariant {
     /// Returns the default value. See [`XsumVariant::default`] for more information.
     fn new() -> Self {
@@ -0,0 +1,25 @@
+// `use xsum::*` must bring in `Xsum` without any other trait whose methods have the same names
+use xsum::*;
+
+#[test]
+fn glob_import() {
+    let mut xsmall = XsumSmall::new();
+    xsmall.add(1.0);
+    xsmall.add_list(&[2.0, 3.0]);
+    assert_eq!(xsmall.sum(), 6.0, "xsmall.sum() with use xsum::*");
+
+    let mut xlarge = XsumLarge::new();
+    xlarge.add(1.0);
+    xlarge.add_list(&[2.0, 3.0]);
+    assert_eq!(xlarge.sum(), 6.0, "xlarge.sum() with use xsum::*");
+
+    let mut xauto = XsumAuto::new();
+    xauto.add(1.0);
+    xauto.add_list(&[2.0, 3.0]);
+    assert_eq!(xauto.sum(), 6.0, "xauto.sum() with use xsum::*");
+    xauto.clear();
+    assert!(
+        xauto.is_empty(),
+        "xauto.is_empty() after clear() with use xsum::*"
+    );
+}
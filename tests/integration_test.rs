@@ -1,6 +1,19 @@
 mod common;
 
+use std::{
+    cmp::Ordering,
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
 use crate::common::same_value;
+use xsum::{
+    reference::{assert_agrees, reference_sum},
+    summation::{KahanSum, NaiveSum, NeumaierSum, PairwiseSum},
+    testgen::TestGen,
+    ConcurrentXsum, ExactAddend, ExactF64, ParseHexError, RoundingMode, Sign, SpecialValuePolicy,
+    SumFlags, Xsum, XsumAuto, XsumError, XsumExt, XsumLarge, XsumProfile, XsumSmall, XsumVariant,
+};
 
 const INFINITY: f64 = f64::INFINITY;
 #[allow(non_upper_case_globals)]
@@ -23,6 +36,14 @@ fn basic() {
         0.30000000000000004,
     );
     same_value(&[1e30, 0.1, -1e30], 0.1);
+    same_value(&[-6.0], -6.0);
+    same_value(&[-1.0, -2.0, -3.0], -6.0);
+    same_value(&[-0.1, -0.1], -0.2);
+    same_value(&[-1.0, -1.1102230246251565e-16], -1.0);
+    same_value(&[-3.0, -2.220446049250313e-16], -3.0);
+    same_value(&[-3.0, -3.3306690738754696e-16], -3.0000000000000004);
+    same_value(&[-1.0, -2.220446049250313e-16], -1.0000000000000002);
+    same_value(&[-1.0, -3.3306690738754696e-16], -1.0000000000000004);
 }
 
 #[test]
@@ -220,9 +241,2372 @@ fn large_sized_array() {
     same_value(&[0.1; 2_000], 200.0);
     same_value(&[1e308; 1_000], INFINITY);
     same_value(&[-1e308; 1_000], -INFINITY);
+    // more than 2,048 values with the same exponent fill the large chunks to the top bit
+    same_value(&[1.0; 5_000], 5_000.0);
+    same_value(&[-1.999_999_999_999_999_8; 4_096], -8_191.999_999_999_999);
+    same_value(&[4_294_967_295.0; 3_000], 12_884_901_885_000.0);
+    // the sum reaches the top chunk of the small accumulator
+    same_value(&[1e308; 20_000], INFINITY);
+    same_value(&[-1e308; 20_000], -INFINITY);
     same_value(&[1e-308; 2_000], 1.9999999999999997e-305);
     same_value(&[NaN; 2_000], NaN);
     same_value(&[INFINITY; 2_000], INFINITY);
     same_value(&[-INFINITY; 2_000], -INFINITY);
     same_value(&[-0.0; 2_000], -0.0);
 }
+
+#[test]
+fn introspection() {
+    let vec = [1.0, NaN, -INFINITY, 2.5];
+
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(&vec);
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_list(&vec);
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_list(&vec);
+
+    assert_eq!(xsumsmall.len(), 4, "xsumsmall must have 4 values");
+    assert_eq!(xsumlarge.len(), 4, "xsumlarge must have 4 values");
+    assert_eq!(xsumauto.len(), 4, "xsumauto must have 4 values");
+    assert!(xsumsmall.has_nan(), "xsumsmall must have NaN");
+    assert!(xsumlarge.has_nan(), "xsumlarge must have NaN");
+    assert!(xsumauto.has_nan(), "xsumauto must have NaN");
+    assert!(xsumsmall.has_infinity(), "xsumsmall must have Inf");
+    assert!(xsumlarge.has_infinity(), "xsumlarge must have Inf");
+    assert!(xsumauto.has_infinity(), "xsumauto must have Inf");
+
+    xsumsmall.clear();
+    xsumlarge.clear();
+    xsumauto.clear();
+    assert!(xsumsmall.is_empty(), "xsumsmall must be empty");
+    assert!(xsumlarge.is_empty(), "xsumlarge must be empty");
+    assert!(xsumauto.is_empty(), "xsumauto must be empty");
+    assert_eq!(xsumsmall.nan_payload(), None, "xsumsmall must have no NaN");
+    assert_eq!(xsumlarge.nan_payload(), None, "xsumlarge must have no NaN");
+    assert_eq!(xsumauto.nan_payload(), None, "xsumauto must have no NaN");
+
+    xsumauto.add_list(&[0.5; 2_000]);
+    assert_eq!(xsumauto.len(), 2_000, "xsumauto must have 2,000 values");
+    assert!(
+        xsumauto.debug_dump().starts_with("large accumulator"),
+        "xsumauto must use XsumLarge"
+    );
+}
+
+fn same_try_sum(vec: &[f64], expected: Result<f64, XsumError>) {
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(vec);
+    assert_eq!(xsumsmall.try_sum(), expected, "xsumsmall.try_sum()");
+
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_list(vec);
+    assert_eq!(xsumlarge.try_sum(), expected, "xsumlarge.try_sum()");
+
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_list(vec);
+    assert_eq!(xsumauto.try_sum(), expected, "xsumauto.try_sum()");
+}
+
+// `try_sum()?` in a function returning a boxed error
+fn boxed_try_sum(vec: &[f64]) -> Result<f64, Box<dyn std::error::Error>> {
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(vec);
+    Ok(xsumsmall.try_sum()?)
+}
+
+#[test]
+fn try_sum() {
+    assert_eq!(
+        boxed_try_sum(&[1.0, 2.0]).ok(),
+        Some(3.0),
+        "boxed_try_sum() of finite values"
+    );
+    assert_eq!(
+        boxed_try_sum(&[-INFINITY, INFINITY])
+            .err()
+            .map(|error| error.to_string()),
+        Some("both +Inf and -Inf were added".to_owned()),
+        "boxed_try_sum() keeps the XsumError message"
+    );
+    same_try_sum(&[1.0, 2.0, 3.0], Ok(6.0));
+    same_try_sum(&[1e308, 1e308, -1e308], Ok(1e308));
+    same_try_sum(
+        &[f64::from_bits(0x7ff8_0000_0000_0001), INFINITY],
+        Err(XsumError::NaNInput {
+            payload: 0x8_0000_0000_0001,
+        }),
+    );
+    same_try_sum(
+        &[INFINITY, 1.0],
+        Err(XsumError::InfiniteInput {
+            sign: Sign::Positive,
+        }),
+    );
+    same_try_sum(&[-INFINITY, INFINITY], Err(XsumError::OppositeInfinities));
+    same_try_sum(
+        &[8.98846567431158e+307, 8.98846567431158e+307],
+        Err(XsumError::Overflow {
+            sign: Sign::Positive,
+        }),
+    );
+    same_try_sum(
+        &[-1e308; 2_000],
+        Err(XsumError::Overflow {
+            sign: Sign::Negative,
+        }),
+    );
+
+    // a carry out of the top chunk is an overflow, not a NaN input
+    for (value, sign) in [(f64::MAX, Sign::Positive), (-f64::MAX, Sign::Negative)] {
+        let mut xsumsmall = XsumSmall::new();
+        xsumsmall.add_repeated_list(&[(value, 1 << 40); 64]);
+        assert_eq!(
+            xsumsmall.try_sum(),
+            Err(XsumError::Overflow { sign }),
+            "xsumsmall.try_sum() after a carry out of the top chunk"
+        );
+        let (sum, flags) = xsumsmall.sum_with_flags();
+        assert_eq!(sum, value * INFINITY, "xsumsmall.sum() after the carry");
+        assert!(flags.overflow, "xsumsmall.sum_with_flags() after the carry");
+    }
+}
+
+// Like is_valid() in common, but NaN payloads must match unless `expected` is f64::NAN
+fn same_bits(actual: f64, expected: f64) -> bool {
+    actual.to_bits() == expected.to_bits()
+        || (actual.is_nan() && expected.to_bits() == f64::NAN.to_bits())
+}
+
+fn same_value_with_policy(vec: &[f64], policy: SpecialValuePolicy, expected: f64, len: usize) {
+    let mut xsumsmall = XsumSmall::with_policy(policy);
+    xsumsmall.add_list(vec);
+    assert_eq!(xsumsmall.len(), len, "xsumsmall.len() with {policy:?}");
+    let actual = xsumsmall.sum();
+    assert!(
+        same_bits(actual, expected),
+        "xsumsmall.sum() with {policy:?} must be {expected}, got {actual}"
+    );
+    xsumsmall.clear();
+    assert_eq!(xsumsmall.policy(), policy, "xsumsmall.clear() keeps policy");
+
+    let mut xsumlarge = XsumLarge::with_policy(policy);
+    xsumlarge.add_list(vec);
+    assert_eq!(xsumlarge.len(), len, "xsumlarge.len() with {policy:?}");
+    let actual = xsumlarge.sum();
+    assert!(
+        same_bits(actual, expected),
+        "xsumlarge.sum() with {policy:?} must be {expected}, got {actual}"
+    );
+    xsumlarge.clear();
+    assert_eq!(xsumlarge.policy(), policy, "xsumlarge.clear() keeps policy");
+
+    let mut xsumauto = XsumAuto::with_policy(policy);
+    xsumauto.add_list(vec);
+    assert_eq!(xsumauto.len(), len, "xsumauto.len() with {policy:?}");
+    let actual = xsumauto.sum();
+    assert!(
+        same_bits(actual, expected),
+        "xsumauto.sum() with {policy:?} must be {expected}, got {actual}"
+    );
+    xsumauto.clear();
+    assert_eq!(xsumauto.policy(), policy, "xsumauto.clear() keeps policy");
+}
+
+#[test]
+fn special_value_policy() {
+    let nan1 = f64::from_bits(0x7ff8_0000_0000_0001);
+    let nan2 = f64::from_bits(0x7ff8_0000_0000_0002);
+    let vec = [1.0, nan1, 2.0, nan2];
+
+    same_value_with_policy(&vec, SpecialValuePolicy::Propagate, nan2, 4);
+    same_value_with_policy(&vec, SpecialValuePolicy::FirstNaN, nan1, 4);
+    same_value_with_policy(&vec, SpecialValuePolicy::IgnoreNaN, 3.0, 2);
+    same_value_with_policy(&vec, SpecialValuePolicy::IgnoreNonFinite, 3.0, 2);
+    same_value_with_policy(&vec, SpecialValuePolicy::Error, 3.0, 2);
+
+    let vec = [INFINITY, 1.0, -INFINITY, NaN];
+    same_value_with_policy(&vec, SpecialValuePolicy::IgnoreNaN, NaN, 3);
+    same_value_with_policy(&vec, SpecialValuePolicy::IgnoreNonFinite, 1.0, 1);
+
+    // skipped values do not decide the sign of a zero sum
+    same_value_with_policy(
+        &[-0.0, NaN, INFINITY],
+        SpecialValuePolicy::IgnoreNonFinite,
+        -0.0,
+        1,
+    );
+    same_value_with_policy(&[NaN; 2_000], SpecialValuePolicy::IgnoreNaN, -0.0, 0);
+
+    let mut vec = vec![0.5; 2_000];
+    vec.push(-INFINITY);
+    same_value_with_policy(&vec, SpecialValuePolicy::IgnoreNonFinite, 1_000.0, 2_000);
+
+    let mut xsumauto = XsumAuto::with_policy(SpecialValuePolicy::Error);
+    xsumauto.add_list(&vec);
+    assert_eq!(
+        xsumauto.sum(),
+        1_000.0,
+        "Error policy leaves -Inf out of sum()"
+    );
+    assert!(xsumauto.has_infinity(), "Error policy records -Inf");
+    assert_eq!(
+        xsumauto.try_sum(),
+        Err(XsumError::InfiniteInput {
+            sign: Sign::Negative
+        }),
+        "Error policy reports -Inf in try_sum()"
+    );
+}
+
+fn same_flags(vec: &[f64], expected: (f64, SumFlags)) {
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(vec);
+    assert_eq!(
+        xsumsmall.sum_with_flags(),
+        expected,
+        "xsumsmall.sum_with_flags()"
+    );
+
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_list(vec);
+    assert_eq!(
+        xsumlarge.sum_with_flags(),
+        expected,
+        "xsumlarge.sum_with_flags()"
+    );
+
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_list(vec);
+    assert_eq!(
+        xsumauto.sum_with_flags(),
+        expected,
+        "xsumauto.sum_with_flags()"
+    );
+}
+
+#[test]
+fn sum_with_flags() {
+    let exact = SumFlags::default();
+    let inexact = SumFlags {
+        inexact: true,
+        ..SumFlags::default()
+    };
+    let overflow = SumFlags {
+        inexact: true,
+        overflow: true,
+        ..SumFlags::default()
+    };
+    let subnormal = SumFlags {
+        subnormal: true,
+        ..SumFlags::default()
+    };
+
+    same_flags(&[], (-0.0, exact));
+    same_flags(&[1.0, 2.0, 3.0], (6.0, exact));
+    same_flags(&[1e30, 0.1, -1e30], (0.1, exact));
+    same_flags(&[0.1, 0.1, 0.1], (0.30000000000000004, inexact));
+    same_flags(&[1.0, f64::EPSILON / 2.0], (1.0, inexact));
+    same_flags(&[-1.0, -f64::EPSILON / 4.0], (-1.0, inexact));
+    same_flags(&[0.1; 2_000], (200.0, inexact));
+    same_flags(&[5e-324, 5e-324], (1e-323, subnormal));
+    // a subnormal sum is a multiple of the smallest subnormal, so it is exact
+    same_flags(&[1.0, 5e-324, -1.0], (5e-324, subnormal));
+    same_flags(&[1e308; 1_000], (INFINITY, overflow));
+    same_flags(&[INFINITY, 1.0], (INFINITY, exact));
+}
+
+fn same_extended(vec: &[f64], expected: (f64, i64)) {
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(vec);
+    assert_eq!(
+        xsumsmall.sum_extended(),
+        expected,
+        "xsumsmall.sum_extended()"
+    );
+
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_list(vec);
+    assert_eq!(
+        xsumlarge.sum_extended(),
+        expected,
+        "xsumlarge.sum_extended()"
+    );
+
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_list(vec);
+    assert_eq!(xsumauto.sum_extended(), expected, "xsumauto.sum_extended()");
+}
+
+#[test]
+fn sum_extended() {
+    same_extended(&[1.0, 2.0, 3.0], (0.75, 3));
+    same_extended(&[-0.1, -0.2], (-0.6000000000000001, -1));
+    same_extended(&[5e-324], (0.5, -1073));
+    same_extended(&[-0.0], (-0.0, 0));
+    same_extended(&[INFINITY, 1.0], (INFINITY, 0));
+    // 2^1023 * 2,000 = 0.9765625 * 2^1034
+    same_extended(&[8.98846567431158e307; 2_000], (0.9765625, 1034));
+    // the exact sum 2^1024 + 2^971 is a tie, rounded to even
+    same_extended(
+        &[
+            8.98846567431158e307,
+            8.98846567431158e307,
+            1.99584030953472e292,
+        ],
+        (0.5, 1025),
+    );
+}
+
+fn same_scaled(vec: &[f64], k: i32, n: i64, expected: f64) {
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(vec);
+    xsumsmall.scale_pow2(k);
+    xsumsmall.mul_int(n);
+    let actual = xsumsmall.sum();
+    assert!(
+        same_bits(actual, expected),
+        "xsumsmall.sum() scaled by 2^{k} and {n} must be {expected}, got {actual}"
+    );
+
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_list(vec);
+    xsumlarge.scale_pow2(k);
+    xsumlarge.mul_int(n);
+    let actual = xsumlarge.sum();
+    assert!(
+        same_bits(actual, expected),
+        "xsumlarge.sum() scaled by 2^{k} and {n} must be {expected}, got {actual}"
+    );
+
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_list(vec);
+    xsumauto.scale_pow2(k);
+    xsumauto.mul_int(n);
+    let actual = xsumauto.sum();
+    assert!(
+        same_bits(actual, expected),
+        "xsumauto.sum() scaled by 2^{k} and {n} must be {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn scale_and_mul_int() {
+    same_scaled(&[1.0, 2.0, 3.0], 3, 1, 48.0);
+    same_scaled(&[1.0, 2.0, 3.0], -1, -2, -6.0);
+    same_scaled(&[0.1; 10], 0, 10, 10.0);
+    // scaling is exact while the bits stay within the accumulator
+    same_scaled(&[1e308, -1e308, 1.0], 1_000, 1, 2f64.powi(1_000));
+    same_scaled(&[1e308], 1_000, 1, INFINITY);
+    same_scaled(&[1e300, 1.0], 100, 1, 1e300 * 2f64.powi(100));
+    same_scaled(&[5e-324], 1, 1, 1e-323);
+    // bits below the smallest subnormal are rounded, ties to even
+    same_scaled(&[5e-324], -1, 1, 0.0);
+    same_scaled(&[1.5e-323], -1, 1, 1e-323);
+    same_scaled(&[1.0], -1_100, 1, 0.0);
+    same_scaled(&[1.0], -1, 0, 0.0);
+    same_scaled(&[1.0], 0, -3, -3.0);
+    same_scaled(&[-0.0], 0, -1, 0.0);
+    same_scaled(&[0.0], 0, -1, -0.0);
+    same_scaled(&[INFINITY], 5, -2, -INFINITY);
+    same_scaled(&[INFINITY], 0, 0, NaN);
+
+    // only the bits below the smallest subnormal are rounded, not the whole value
+    let vec = [1.0, 2.0_f64.powi(-60), 5e-324];
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(&vec);
+    xsumsmall.scale_pow2(-1);
+    xsumsmall.scale_pow2(1);
+    assert_eq!(
+        xsumsmall.to_hex_string(),
+        "0x1.000000000000001p+0",
+        "xsumsmall.to_hex_string() scaled by 2^-1 and 2^1"
+    );
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_list(&vec);
+    xsumlarge.scale_pow2(-1);
+    xsumlarge.scale_pow2(1);
+    assert_eq!(
+        xsumlarge.to_hex_string(),
+        "0x1.000000000000001p+0",
+        "xsumlarge.to_hex_string() scaled by 2^-1 and 2^1"
+    );
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_list(&vec);
+    xsumauto.scale_pow2(-2);
+    xsumauto.scale_pow2(2);
+    assert_eq!(
+        xsumauto.to_hex_string(),
+        "0x1.000000000000001p+0",
+        "xsumauto.to_hex_string() scaled by 2^-2 and 2^2"
+    );
+    same_scaled(&[1e308], 0, i64::MAX, INFINITY);
+
+    // a value too large for the accumulator is an overflow, not an infinite input
+    let positive_overflow = Err(XsumError::Overflow {
+        sign: Sign::Positive,
+    });
+    let negative_overflow = Err(XsumError::Overflow {
+        sign: Sign::Negative,
+    });
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add(f64::MAX);
+    for _ in 0..3 {
+        xsumsmall.mul_int(i64::MAX);
+    }
+    assert_eq!(
+        xsumsmall.try_sum(),
+        positive_overflow,
+        "xsumsmall.try_sum() after mul_int() overflows"
+    );
+    assert!(
+        xsumsmall.sum_with_flags().1.overflow,
+        "xsumsmall.sum_with_flags() after mul_int() overflows"
+    );
+    assert!(!xsumsmall.has_infinity(), "xsumsmall.has_infinity()");
+    xsumsmall.mul_int(-1);
+    assert_eq!(
+        xsumsmall.try_sum(),
+        negative_overflow,
+        "xsumsmall.try_sum() after negating an overflow"
+    );
+
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add(1.0);
+    xsumlarge.scale_pow2(2_000);
+    assert_eq!(
+        xsumlarge.try_sum(),
+        positive_overflow,
+        "xsumlarge.try_sum() after scale_pow2() overflows"
+    );
+    xsumlarge.scale_pow2(-2_000);
+    assert_eq!(
+        xsumlarge.try_sum(),
+        positive_overflow,
+        "xsumlarge.try_sum() stays an overflow"
+    );
+
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_repeated(-f64::MAX, u64::MAX);
+    assert_eq!(
+        xsumauto.try_sum(),
+        negative_overflow,
+        "xsumauto.try_sum() after add_repeated() overflows"
+    );
+    assert!(
+        xsumauto.sum_with_flags().1.overflow,
+        "xsumauto.sum_with_flags() after add_repeated() overflows"
+    );
+}
+
+fn same_repeated(pairs: &[(f64, u64)], policy: SpecialValuePolicy, expected: f64, len: usize) {
+    let mut xsumsmall = XsumSmall::with_policy(policy);
+    xsumsmall.add_repeated_list(pairs);
+    assert_eq!(xsumsmall.len(), len, "xsumsmall.len()");
+    let actual = xsumsmall.sum();
+    assert!(
+        same_bits(actual, expected),
+        "xsumsmall.sum() of {pairs:?} must be {expected}, got {actual}"
+    );
+
+    let mut xsumlarge = XsumLarge::with_policy(policy);
+    xsumlarge.add_repeated_list(pairs);
+    assert_eq!(xsumlarge.len(), len, "xsumlarge.len()");
+    let actual = xsumlarge.sum();
+    assert!(
+        same_bits(actual, expected),
+        "xsumlarge.sum() of {pairs:?} must be {expected}, got {actual}"
+    );
+
+    let mut xsumauto = XsumAuto::with_policy(policy);
+    for &(value, count) in pairs {
+        xsumauto.add_repeated(value, count);
+    }
+    assert_eq!(xsumauto.len(), len, "xsumauto.len()");
+    let actual = xsumauto.sum();
+    assert!(
+        same_bits(actual, expected),
+        "xsumauto.sum() of {pairs:?} must be {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn add_repeated() {
+    let propagate = SpecialValuePolicy::Propagate;
+    same_repeated(&[(1.0, 3), (2.0, 2)], propagate, 7.0, 5);
+    same_repeated(&[(0.1, 10)], propagate, 1.0, 10);
+    same_repeated(
+        &[(0.1, 1 << 40), (-0.1, (1 << 40) - 1)],
+        propagate,
+        0.1,
+        (1 << 41) - 1,
+    );
+    same_repeated(
+        &[(1e16, 1_000_000), (1.0, 3), (-1e16, 1_000_000)],
+        propagate,
+        3.0,
+        2_000_003,
+    );
+    same_repeated(
+        &[(5e-324, u64::MAX)],
+        propagate,
+        9.113_902_524_445_497e-305,
+        usize::MAX,
+    );
+    same_repeated(&[(f64::MAX, 3), (-f64::MAX, 2)], propagate, f64::MAX, 5);
+    same_repeated(&[(1e308, u64::MAX)], propagate, INFINITY, usize::MAX);
+    same_repeated(&[(-f64::MAX, u64::MAX)], propagate, -INFINITY, usize::MAX);
+    same_repeated(&[(-f64::MAX, 1 << 40); 64], propagate, -INFINITY, 64 << 40);
+    same_repeated(&[(1.0, 0)], propagate, -0.0, 0);
+    same_repeated(&[(-0.0, 5)], propagate, -0.0, 5);
+    same_repeated(&[(-0.0, 5), (0.0, 1)], propagate, 0.0, 6);
+    same_repeated(&[(INFINITY, 4), (1.0, 4)], propagate, INFINITY, 8);
+    same_repeated(&[(NaN, 4), (1.0, 4)], propagate, NaN, 8);
+    same_repeated(&[(NaN, 4), (1.0, 4)], SpecialValuePolicy::IgnoreNaN, 4.0, 4);
+
+    // XsumAuto switches to XsumLarge once it has more values than the threshold
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_repeated(0.5, 2_000);
+    assert!(
+        xsumauto.debug_dump().starts_with("large accumulator"),
+        "xsumauto.add_repeated() must switch to XsumLarge"
+    );
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_repeated_list(&[(0.5, 1_000), (0.25, 1_000)]);
+    assert!(
+        xsumauto.debug_dump().starts_with("large accumulator"),
+        "xsumauto.add_repeated_list() must switch to XsumLarge"
+    );
+    assert_eq!(xsumauto.sum(), 750.0, "xsumauto.sum() after the switch");
+}
+
+fn same_integer_sum(i64s: &[i64], u64s: &[u64], i128s: &[i128], floats: &[f64], expected: f64) {
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_i64_list(i64s);
+    xsumsmall.add_u64_list(u64s);
+    xsumsmall.add_i128_list(i128s);
+    xsumsmall.add_list(floats);
+    assert_eq!(xsumsmall.sum(), expected, "xsumsmall.sum()");
+
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_list(floats);
+    xsumlarge.add_i64_list(i64s);
+    xsumlarge.add_u64_list(u64s);
+    xsumlarge.add_i128_list(i128s);
+    assert_eq!(xsumlarge.sum(), expected, "xsumlarge.sum()");
+
+    let mut xsumauto = XsumAuto::new();
+    for &value in i64s {
+        xsumauto.add_i64(value);
+    }
+    for &value in u64s {
+        xsumauto.add_u64(value);
+    }
+    for &value in i128s {
+        xsumauto.add_i128(value);
+    }
+    xsumauto.add_list(floats);
+    assert_eq!(xsumauto.sum(), expected, "xsumauto.sum()");
+    assert_eq!(
+        xsumauto.len(),
+        i64s.len() + u64s.len() + i128s.len() + floats.len(),
+        "xsumauto.len()"
+    );
+}
+
+#[test]
+fn add_integers() {
+    // 2^53 + 1 is not an f64, but the sum is only rounded once
+    same_integer_sum(&[(1 << 53) + 1, 1], &[], &[], &[], 9_007_199_254_740_994.0);
+    same_integer_sum(&[(1 << 53) + 1], &[], &[], &[0.5], 9_007_199_254_740_994.0);
+    same_integer_sum(&[(1 << 53) + 1], &[], &[], &[-0.5], 9_007_199_254_740_992.0);
+    same_integer_sum(
+        &[i64::MIN],
+        &[u64::MAX],
+        &[i128::MIN, i128::MAX],
+        &[-9.223372036854776e18],
+        -2.0,
+    );
+    same_integer_sum(&[], &[], &[i128::MAX], &[], 1.7014118346046923e38);
+    same_integer_sum(&[], &[], &[1], &[-1e-300, 1e-300], 1.0);
+    same_integer_sum(&[1; 3_000], &[], &[], &[0.1; 3_000], 3_300.0);
+    // integer zeros are unsigned, so they give +0.0 like 0.0 would
+    same_integer_sum(&[0], &[], &[], &[-0.0], 0.0);
+    same_integer_sum(&[-5], &[5], &[], &[], 0.0);
+
+    // XsumAuto switches to XsumLarge once it has more values than the threshold
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_i64_list(&[1; 2_000]);
+    assert!(
+        xsumauto.debug_dump().starts_with("large accumulator"),
+        "xsumauto.add_i64_list() must switch to XsumLarge"
+    );
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_u64_list(&[1; 2_000]);
+    assert!(
+        xsumauto.debug_dump().starts_with("large accumulator"),
+        "xsumauto.add_u64_list() must switch to XsumLarge"
+    );
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_i128_list(&[1; 2_000]);
+    assert!(
+        xsumauto.debug_dump().starts_with("large accumulator"),
+        "xsumauto.add_i128_list() must switch to XsumLarge"
+    );
+    let mut xsumauto = XsumAuto::new();
+    for i in 0..2_000 {
+        match i % 3 {
+            0 => xsumauto.add_i64(-1),
+            1 => xsumauto.add_u64(2),
+            _ => xsumauto.add_i128(3),
+        }
+    }
+    assert!(
+        xsumauto.debug_dump().starts_with("large accumulator"),
+        "xsumauto.add_i64(), add_u64() and add_i128() must switch to XsumLarge"
+    );
+    assert_eq!(xsumauto.sum(), 2_665.0, "xsumauto.sum() after the switch");
+}
+
+fn same_generic<T: ExactAddend>(vec: &[T], expected: f64) {
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_slice(vec);
+    assert_eq!(xsumsmall.sum(), expected, "xsumsmall.add_slice()");
+    xsumsmall.clear();
+    for &value in vec {
+        xsumsmall.add_generic(value);
+    }
+    assert_eq!(xsumsmall.sum(), expected, "xsumsmall.add_generic()");
+
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_slice(vec);
+    assert_eq!(xsumlarge.sum(), expected, "xsumlarge.add_slice()");
+    xsumlarge.clear();
+    for &value in vec {
+        xsumlarge.add_generic(value);
+    }
+    assert_eq!(xsumlarge.sum(), expected, "xsumlarge.add_generic()");
+
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_slice(vec);
+    assert_eq!(xsumauto.sum(), expected, "xsumauto.add_slice()");
+    xsumauto.clear();
+    for &value in vec {
+        xsumauto.add_generic(value);
+    }
+    assert_eq!(xsumauto.sum(), expected, "xsumauto.add_generic()");
+
+    for mut xsumvariant in [
+        XsumVariant::Small(XsumSmall::new()),
+        XsumVariant::Large(XsumLarge::new()),
+        XsumVariant::Auto(XsumAuto::new()),
+    ] {
+        xsumvariant.add_slice(vec);
+        assert_eq!(xsumvariant.sum(), expected, "xsumvariant.add_slice()");
+        xsumvariant.clear();
+        for &value in vec {
+            xsumvariant.add_generic(value);
+        }
+        assert_eq!(xsumvariant.sum(), expected, "xsumvariant.add_generic()");
+    }
+
+    assert_eq!(vec.xsum(), expected, "vec.xsum()");
+}
+
+#[test]
+fn add_generic() {
+    same_generic(&[0.1f32; 10], 1.000_000_014_901_161_2);
+    same_generic(&[0.1f32; 2_000], 200.000_002_980_232_24);
+    same_generic(&[f32::MAX, f32::MAX], 6.805_646_932_770_577e38);
+    same_generic(&[f32::MIN_POSITIVE / 2.0], 5.877_471_754_111_438e-39);
+    same_generic(&[i32::MIN, i32::MIN, i32::MAX], -2_147_483_649.0);
+    same_generic(&[u32::MAX; 3_000], 12_884_901_885_000.0);
+    same_generic(&[i8::MIN, i8::MAX], -1.0);
+    same_generic(&[u8::MAX, 1], 256.0);
+    same_generic(&[i16::MIN, 1], -32_767.0);
+    same_generic(&[u16::MAX, 1], 65_536.0);
+    same_generic(&[1e16, 1.0, -1e16], 1.0);
+}
+
+// Signaling NaNs may be quieted when converted to f64, and the sum clears the sign of a NaN,
+// so only the rest of the payload is compared
+fn same_decoded(actual: f64, expected: f64) -> bool {
+    const IGNORED_BITS: u64 = (1 << 63) | (1 << 51);
+    if expected.is_nan() {
+        return actual.to_bits() | IGNORED_BITS == expected.to_bits() | IGNORED_BITS;
+    }
+    actual.to_bits() == expected.to_bits()
+}
+
+#[test]
+fn add_half_bits() {
+    // a bfloat16 is the upper half of an f32
+    for bits in 0..=u16::MAX {
+        let expected = f64::from(f32::from_bits(u32::from(bits) << 16));
+        let mut xsumsmall = XsumSmall::new();
+        xsumsmall.add_bf16_bits(&[bits]);
+        let actual = xsumsmall.sum();
+        assert!(
+            same_decoded(actual, expected),
+            "bfloat16 {bits:#06x} must be {expected}, got {actual}"
+        );
+    }
+
+    let f16_values: [(u16, f64); 9] = [
+        (0x0001, 5.960_464_477_539_063e-8),
+        (0x03ff, 6.097_555_160_522_461e-5),
+        (0x0400, 6.103_515_625e-5),
+        (0x3c00, 1.0),
+        (0x7bff, 65_504.0),
+        (0xc000, -2.0),
+        (0x8000, -0.0),
+        (0x7c00, INFINITY),
+        (0xfc00, -INFINITY),
+    ];
+    for (bits, expected) in f16_values {
+        let mut xsumsmall = XsumSmall::new();
+        xsumsmall.add_f16_bits(&[bits]);
+        let actual = xsumsmall.sum();
+        assert!(
+            same_decoded(actual, expected),
+            "binary16 {bits:#06x} must be {expected}, got {actual}"
+        );
+    }
+
+    // NaN payloads are kept, in the upper bits of the f64 payload
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_f16_bits(&[0x7e01]);
+    assert_eq!(
+        xsumsmall.nan_payload(),
+        Some(0x8_0400_0000_0000),
+        "nan_payload()"
+    );
+
+    // sums over all finite patterns are exact, and agree with the f64 inputs
+    let finite: Vec<u16> = (0..=u16::MAX)
+        .filter(|bits| bits & 0x7c00 != 0x7c00)
+        .collect();
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(&[65_504.0, 1e-10]);
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_f16_bits(&finite);
+    xsumlarge.add_f16_bits(&[0x7bff]);
+    xsumlarge.add(1e-10);
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_f16_bits(&finite);
+    xsumauto.add_bf16_bits(&[0x477f, 0x477f, 0xc77f]);
+    xsumauto.add(1e-10);
+    // every finite pattern has its negation in the list
+    assert_eq!(xsumlarge.sum(), xsumsmall.sum(), "xsumlarge.sum()");
+    assert_eq!(xsumauto.sum(), 65_280.000_000_000_1, "xsumauto.sum()");
+    assert_eq!(xsumlarge.len(), 63_490, "xsumlarge.len()");
+    assert!(!xsumauto.has_nan(), "xsumauto.has_nan()");
+    assert!(
+        xsumauto.debug_dump().starts_with("large accumulator"),
+        "xsumauto.add_f16_bits() must switch to XsumLarge"
+    );
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_bf16_bits(&[0x3f80; 2_000]);
+    assert!(
+        xsumauto.debug_dump().starts_with("large accumulator"),
+        "xsumauto.add_bf16_bits() must switch to XsumLarge"
+    );
+    assert_eq!(xsumauto.sum(), 2_000.0, "xsumauto.sum() of bf16 ones");
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn add_half_values() {
+    for bits in 0..=u16::MAX {
+        let expected = half::f16::from_bits(bits).to_f64();
+        let mut xsumsmall = XsumSmall::new();
+        xsumsmall.add_f16_bits(&[bits]);
+        let actual = xsumsmall.sum();
+        assert!(
+            same_decoded(actual, expected),
+            "binary16 {bits:#06x} must be {expected}, got {actual}"
+        );
+    }
+
+    let values = [half::f16::from_f32(0.1), half::f16::MAX, half::f16::MIN];
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_slice(&values);
+    xsumauto.add_generic(half::bf16::from_f32(0.1));
+    assert_eq!(xsumauto.sum(), 0.200_073_242_187_5, "xsumauto.sum()");
+    assert_eq!(values.xsum(), 0.099_975_585_937_5, "values.xsum()");
+}
+
+fn same_narrow(vec: &[f64], expected: (f32, u16, u16)) {
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(vec);
+    let actual = (
+        xsumsmall.sum_f32(),
+        xsumsmall.sum_f16_bits(),
+        xsumsmall.sum_bf16_bits(),
+    );
+    assert!(
+        same_narrow_bits(actual, expected),
+        "xsumsmall narrow sums must be {expected:?}, got {actual:?}"
+    );
+
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_list(vec);
+    let actual = (
+        xsumlarge.sum_f32(),
+        xsumlarge.sum_f16_bits(),
+        xsumlarge.sum_bf16_bits(),
+    );
+    assert!(
+        same_narrow_bits(actual, expected),
+        "xsumlarge narrow sums must be {expected:?}, got {actual:?}"
+    );
+
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_list(vec);
+    let actual = (
+        xsumauto.sum_f32(),
+        xsumauto.sum_f16_bits(),
+        xsumauto.sum_bf16_bits(),
+    );
+    assert!(
+        same_narrow_bits(actual, expected),
+        "xsumauto narrow sums must be {expected:?}, got {actual:?}"
+    );
+}
+
+fn same_narrow_bits(actual: (f32, u16, u16), expected: (f32, u16, u16)) -> bool {
+    (actual.0.to_bits() == expected.0.to_bits() || (actual.0.is_nan() && expected.0.is_nan()))
+        && actual.1 == expected.1
+        && actual.2 == expected.2
+}
+
+#[test]
+fn sum_narrow() {
+    same_narrow(&[1.0, 2.0, 3.0], (6.0, 0x4600, 0x40c0));
+    // 1 + 2^-24 is a tie for f32, which the tiny term breaks, so the f64 sum rounds wrongly
+    same_narrow(
+        &[1.0, 5.960_464_477_539_063e-8, 1e-30],
+        (1.000_000_1, 0x3c00, 0x3f80),
+    );
+    same_narrow(&[-1.0, -5.960_464_477_539_063e-8], (-1.0, 0xbc00, 0xbf80));
+    same_narrow(&[0.1; 2_000], (200.0, 0x5a40, 0x4348));
+    // subnormals and underflow, keeping the sign
+    same_narrow(&[1.401_298_464_324_817e-45; 2], (2.8e-45, 0x0000, 0x0000));
+    same_narrow(&[1e-45, 1e-45], (1e-45, 0x0000, 0x0000));
+    same_narrow(&[6e-8], (6e-8, 0x0001, 0x3381));
+    same_narrow(&[-1e-50], (-0.0, 0x8000, 0x8000));
+    same_narrow(&[0.0, -0.0], (0.0, 0x0000, 0x0000));
+    // overflow of the narrow formats
+    same_narrow(&[65_520.0], (65_520.0, 0x7c00, 0x4780));
+    same_narrow(&[-65_519.0], (-65_519.0, 0xfbff, 0xc780));
+    same_narrow(&[1e39, -1e30], (INFINITY as f32, 0x7c00, 0x7f80));
+    same_narrow(&[f64::MAX, f64::MAX], (INFINITY as f32, 0x7c00, 0x7f80));
+    same_narrow(&[-INFINITY, 1.0], (-INFINITY as f32, 0xfc00, 0xff80));
+    same_narrow(&[NaN, 1.0], (NaN as f32, 0x7e00, 0x7fc0));
+
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(&[1.0, 0.125, 0.0625]);
+    assert_eq!(
+        xsumsmall.sum_to_precision(2, -1022, 1023),
+        1.25,
+        "xsumsmall.sum_to_precision()"
+    );
+    assert_eq!(
+        xsumsmall.sum_to_precision(52, -1022, 1023),
+        xsumsmall.sum(),
+        "xsumsmall.sum_to_precision() with the f64 format"
+    );
+    assert_eq!(
+        xsumsmall.sum_to_precision(0, -3, -1),
+        INFINITY,
+        "xsumsmall.sum_to_precision() with overflow"
+    );
+}
+
+fn same_expansion(vec: &[f64], expected: &[f64]) {
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(vec);
+    let expansion = xsumsmall.to_expansion();
+    assert!(
+        expansion.len() == expected.len()
+            && expansion
+                .iter()
+                .zip(expected)
+                .all(|(&a, &e)| same_bits(a, e)),
+        "xsumsmall.to_expansion() must be {expected:?}, got {expansion:?}"
+    );
+
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_list(vec);
+    let expansion = xsumlarge.to_expansion();
+    assert!(
+        expansion.len() == expected.len()
+            && expansion
+                .iter()
+                .zip(expected)
+                .all(|(&a, &e)| same_bits(a, e)),
+        "xsumlarge.to_expansion() must be {expected:?}, got {expansion:?}"
+    );
+
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_list(vec);
+    let expansion = xsumauto.to_expansion();
+    assert!(
+        expansion.len() == expected.len()
+            && expansion
+                .iter()
+                .zip(expected)
+                .all(|(&a, &e)| same_bits(a, e)),
+        "xsumauto.to_expansion() must be {expected:?}, got {expansion:?}"
+    );
+
+    // the expansion round-trips
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_expansion(&expansion);
+    let round_trip = xsumsmall.to_expansion();
+    assert!(
+        round_trip.len() == expected.len()
+            && round_trip
+                .iter()
+                .zip(expected)
+                .all(|(&a, &e)| same_bits(a, e)),
+        "xsumsmall.add_expansion() must round-trip {expected:?}, got {round_trip:?}"
+    );
+}
+
+#[test]
+fn expansion() {
+    same_expansion(&[], &[]);
+    same_expansion(&[1.0, -1.0], &[]);
+    same_expansion(
+        &[0.1, 0.2],
+        &[-2.775_557_561_562_891_4e-17, 0.300_000_000_000_000_04],
+    );
+    same_expansion(&[1e300, 1.0, 1e-300], &[1e-300, 1.0, 1e300]);
+    same_expansion(&[5e-324, 1.0], &[5e-324, 1.0]);
+    // rounding to nearest can make the smaller terms negative
+    same_expansion(
+        &[1.0, 1.5 * f64::EPSILON, 5e-324],
+        &[
+            5e-324,
+            -1.110_223_024_625_156_5e-16,
+            1.000_000_000_000_000_4,
+        ],
+    );
+    // nothing beyond the range of f64, nor NaN or infinity, is an expansion
+    same_expansion(&[f64::MAX, f64::MAX], &[INFINITY]);
+    same_expansion(&[-f64::MAX, -f64::MAX, 1.0], &[-INFINITY]);
+    same_expansion(&[NaN, 1.0], &[NaN]);
+    same_expansion(&[INFINITY, 1.0], &[INFINITY]);
+
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_double_double(1.0, 1e-30);
+    xsumsmall.add_double_double(-1.0, 1e-30);
+    assert_eq!(xsumsmall.to_expansion(), vec![2e-30], "add_double_double()");
+}
+
+fn same_interval(vec: &[f64], expected: (f64, f64)) {
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(vec);
+    let actual = xsumsmall.sum_interval();
+    assert!(
+        same_bits(actual.0, expected.0) && same_bits(actual.1, expected.1),
+        "xsumsmall.sum_interval() must be {expected:?}, got {actual:?}"
+    );
+
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_list(vec);
+    let actual = xsumlarge.sum_interval();
+    assert!(
+        same_bits(actual.0, expected.0) && same_bits(actual.1, expected.1),
+        "xsumlarge.sum_interval() must be {expected:?}, got {actual:?}"
+    );
+
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_list(vec);
+    let actual = xsumauto.sum_interval();
+    assert!(
+        same_bits(actual.0, expected.0) && same_bits(actual.1, expected.1),
+        "xsumauto.sum_interval() must be {expected:?}, got {actual:?}"
+    );
+}
+
+#[test]
+fn sum_interval() {
+    same_interval(&[], (-0.0, -0.0));
+    same_interval(&[1.0, -1.0], (0.0, 0.0));
+    same_interval(&[-0.0, -0.0], (-0.0, -0.0));
+    same_interval(&[1.5, 2.25], (3.75, 3.75));
+    same_interval(&[0.1, 0.2], (0.3, 0.300_000_000_000_000_04));
+    same_interval(&[-0.1, -0.2], (-0.300_000_000_000_000_04, -0.3));
+    same_interval(&[1.0, 1e-300], (1.0, 1.000_000_000_000_000_2));
+    same_interval(&[1.0, -1e-300], (0.999_999_999_999_999_9, 1.0));
+    same_interval(&[5e-324, 1e-323], (1.5e-323, 1.5e-323));
+    same_interval(&[1e-300, -1e-300, 5e-324], (5e-324, 5e-324));
+    // beyond the largest f64, the other bound is infinite
+    same_interval(&[f64::MAX, 1.0], (f64::MAX, INFINITY));
+    same_interval(&[f64::MAX, f64::MAX], (f64::MAX, INFINITY));
+    same_interval(&[-f64::MAX, -f64::MAX, 1.0], (-INFINITY, -f64::MAX));
+    same_interval(
+        &[f64::MAX, -1.0],
+        (f64::from_bits(f64::MAX.to_bits() - 1), f64::MAX),
+    );
+    same_interval(&[f64::MAX, f64::MAX, -f64::MAX], (f64::MAX, f64::MAX));
+    // special values are both bounds
+    same_interval(&[INFINITY, 1.0], (INFINITY, INFINITY));
+    same_interval(&[-INFINITY, 1.0], (-INFINITY, -INFINITY));
+    same_interval(&[INFINITY, -INFINITY], (NaN, NaN));
+    same_interval(&[NaN, 1.0], (NaN, NaN));
+}
+
+fn same_hex(vec: &[f64], expected: &str) {
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(vec);
+    let actual = xsumsmall.to_hex_string();
+    assert_eq!(
+        actual, expected,
+        "xsumsmall.to_hex_string() must be {expected}"
+    );
+
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_list(vec);
+    let actual = xsumlarge.to_hex_string();
+    assert_eq!(
+        actual, expected,
+        "xsumlarge.to_hex_string() must be {expected}"
+    );
+
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_list(vec);
+    let actual = xsumauto.to_hex_string();
+    assert_eq!(
+        actual, expected,
+        "xsumauto.to_hex_string() must be {expected}"
+    );
+
+    // the literal round-trips
+    let mut xsumsmall = XsumSmall::new();
+    assert_eq!(
+        xsumsmall.add_hex_str(expected),
+        Ok(()),
+        "add_hex_str({expected})"
+    );
+    let actual = xsumsmall.to_hex_string();
+    assert_eq!(actual, expected, "add_hex_str() must round-trip {expected}");
+}
+
+fn same_parsed_hex(literal: &str, expected: Result<&str, ParseHexError>) {
+    // nothing is added on error
+    let expected_hex = expected.unwrap_or("-0x0p+0");
+
+    let mut xsumsmall = XsumSmall::new();
+    let result = xsumsmall.add_hex_str(literal).map(|()| expected_hex);
+    let actual = xsumsmall.to_hex_string();
+    assert!(
+        result == expected && actual == expected_hex,
+        "xsumsmall.add_hex_str({literal}) must give {expected:?}, got {result:?} and {actual}"
+    );
+
+    let mut xsumlarge = XsumLarge::new();
+    let result = xsumlarge.add_hex_str(literal).map(|()| expected_hex);
+    let actual = xsumlarge.to_hex_string();
+    assert!(
+        result == expected && actual == expected_hex,
+        "xsumlarge.add_hex_str({literal}) must give {expected:?}, got {result:?} and {actual}"
+    );
+
+    let mut xsumauto = XsumAuto::new();
+    let result = xsumauto.add_hex_str(literal).map(|()| expected_hex);
+    let actual = xsumauto.to_hex_string();
+    assert!(
+        result == expected && actual == expected_hex,
+        "xsumauto.add_hex_str({literal}) must give {expected:?}, got {result:?} and {actual}"
+    );
+}
+
+// `add_hex_str()?` in a function returning a boxed error
+fn boxed_add_hex_str(literal: &str) -> Result<f64, Box<dyn std::error::Error>> {
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_hex_str(literal)?;
+    Ok(xsumsmall.sum())
+}
+
+#[test]
+fn hex_string() {
+    assert_eq!(
+        boxed_add_hex_str("0x1.8p+1").ok(),
+        Some(3.0),
+        "boxed_add_hex_str() of a valid literal"
+    );
+    assert_eq!(
+        boxed_add_hex_str("1.5")
+            .err()
+            .map(|error| error.to_string()),
+        Some("invalid hexadecimal floating-point literal".to_owned()),
+        "boxed_add_hex_str() keeps the ParseHexError message"
+    );
+    same_hex(&[], "-0x0p+0");
+    same_hex(&[-0.0, 0.0], "0x0p+0");
+    same_hex(&[1.0, -1.0], "0x0p+0");
+    same_hex(&[1.0], "0x1p+0");
+    same_hex(&[-0.1], "-0x1.999999999999ap-4");
+    same_hex(&[0.1, 0.2], "0x1.33333333333338p-2");
+    same_hex(
+        &[1.0, 2.0_f64.powi(-1000)],
+        &format!("0x1.{}1p+0", "0".repeat(249)),
+    );
+    same_hex(&[5e-324], "0x1p-1074");
+    same_hex(&[f64::MIN_POSITIVE, -5e-324], "0x1.ffffffffffffep-1023");
+    same_hex(&[f64::MAX, f64::MAX, f64::MAX], "0x1.7ffffffffffff4p+1025");
+    same_hex(&[INFINITY, 1.0], "inf");
+    same_hex(&[-INFINITY, 1.0], "-inf");
+    same_hex(&[INFINITY, -INFINITY], "nan");
+    same_hex(&[-NaN], "nan");
+
+    same_parsed_hex("0x1.8p+1", Ok("0x1.8p+1"));
+    same_parsed_hex("-0X1.8P1", Ok("-0x1.8p+1"));
+    same_parsed_hex("+0x18", Ok("0x1.8p+4"));
+    same_parsed_hex("0x.0018p16", Ok("0x1.8p+4"));
+    same_parsed_hex("0x18.p-4", Ok("0x1.8p+0"));
+    same_parsed_hex("0x0000.000p+99999999999999999999", Ok("0x0p+0"));
+    same_parsed_hex("-0x0p0", Ok("-0x0p+0"));
+    same_parsed_hex("0x1p-1074", Ok("0x1p-1074"));
+    same_parsed_hex("0x3p-1075", Err(ParseHexError::Inexact));
+    same_parsed_hex("0x2p-1075", Ok("0x1p-1074"));
+    same_parsed_hex("0x1p+1068", Ok("0x1p+1068"));
+    same_parsed_hex("0x1p+1069", Err(ParseHexError::OutOfRange));
+    same_parsed_hex("0x1p-99999999999999999999", Err(ParseHexError::Inexact));
+    same_parsed_hex("Infinity", Ok("inf"));
+    same_parsed_hex("-INF", Ok("-inf"));
+    same_parsed_hex("-nan", Ok("nan"));
+    for literal in [
+        "", "-", "0x", "0x.", "0x.p1", "1.5", "0x1p", "0x1p+", "0x1.2.3", "0xg", "--0x1", " 0x1",
+        "0x1 ", "in",
+    ] {
+        same_parsed_hex(literal, Err(ParseHexError::Invalid));
+    }
+
+    // XsumAuto switches to XsumLarge once it has more values than the threshold
+    let mut xsumauto = XsumAuto::new();
+    for _ in 0..2_000 {
+        assert_eq!(
+            xsumauto.add_hex_str("0x1p-1"),
+            Ok(()),
+            "xsumauto.add_hex_str()"
+        );
+    }
+    assert!(
+        xsumauto.debug_dump().starts_with("large accumulator"),
+        "xsumauto.add_hex_str() must switch to XsumLarge"
+    );
+    assert_eq!(xsumauto.sum(), 1_000.0, "xsumauto.sum() after the switch");
+}
+
+fn same_scaled_integer(vec: &[f64], expected: Result<(Sign, Vec<u64>, i64), XsumError>) {
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(vec);
+    let actual = xsumsmall.to_scaled_integer();
+    assert_eq!(actual, expected, "xsumsmall.to_scaled_integer()");
+
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_list(vec);
+    let actual = xsumlarge.to_scaled_integer();
+    assert_eq!(actual, expected, "xsumlarge.to_scaled_integer()");
+
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_list(vec);
+    let actual = xsumauto.to_scaled_integer();
+    assert_eq!(actual, expected, "xsumauto.to_scaled_integer()");
+
+    // the representation round-trips
+    if let Ok((sign, magnitude, exp)) = expected {
+        let mut xsumsmall = XsumSmall::from_scaled_integer(sign, &magnitude, exp)
+            .expect("XsumSmall::from_scaled_integer()");
+        let actual = xsumsmall.to_scaled_integer();
+        assert_eq!(
+            actual,
+            Ok((sign, magnitude.clone(), exp)),
+            "XsumSmall round trip"
+        );
+
+        let mut xsumlarge = XsumLarge::from_scaled_integer(sign, &magnitude, exp)
+            .expect("XsumLarge::from_scaled_integer()");
+        let actual = xsumlarge.to_scaled_integer();
+        assert_eq!(
+            actual,
+            Ok((sign, magnitude.clone(), exp)),
+            "XsumLarge round trip"
+        );
+
+        let mut xsumauto = XsumAuto::from_scaled_integer(sign, &magnitude, exp)
+            .expect("XsumAuto::from_scaled_integer()");
+        let actual = xsumauto.to_scaled_integer();
+        assert_eq!(actual, Ok((sign, magnitude, exp)), "XsumAuto round trip");
+    }
+}
+
+fn same_i128_scaled(vec: &[f64], exp: i64, expected: Option<i128>) {
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(vec);
+    let actual = xsumsmall.to_i128_scaled(exp);
+    assert_eq!(actual, expected, "xsumsmall.to_i128_scaled({exp})");
+
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_list(vec);
+    let actual = xsumlarge.to_i128_scaled(exp);
+    assert_eq!(actual, expected, "xsumlarge.to_i128_scaled({exp})");
+
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_list(vec);
+    let actual = xsumauto.to_i128_scaled(exp);
+    assert_eq!(actual, expected, "xsumauto.to_i128_scaled({exp})");
+}
+
+#[test]
+fn scaled_integer() {
+    same_scaled_integer(&[], Ok((Sign::Negative, vec![], 0)));
+    same_scaled_integer(&[2.0, -2.0], Ok((Sign::Positive, vec![], 0)));
+    same_scaled_integer(&[1.0], Ok((Sign::Positive, vec![1], 0)));
+    same_scaled_integer(&[-12.0], Ok((Sign::Negative, vec![3], 2)));
+    same_scaled_integer(
+        &[0.1],
+        Ok((Sign::Positive, vec![3_602_879_701_896_397], -55)),
+    );
+    same_scaled_integer(&[5e-324], Ok((Sign::Positive, vec![1], -1074)));
+    same_scaled_integer(
+        &[2.0_f64.powi(64), 1.0, -2.0_f64.powi(-10)],
+        Ok((Sign::Positive, vec![1_023, 1_024], -10)),
+    );
+    // the exact value is kept beyond the range of f64
+    same_scaled_integer(
+        &[f64::MAX, f64::MAX],
+        Ok((Sign::Positive, vec![(1 << 53) - 1], 972)),
+    );
+    same_scaled_integer(&[NaN, 1.0], Err(XsumError::NaNInput { payload: 1 << 51 }));
+    same_scaled_integer(
+        &[-INFINITY, 1.0],
+        Err(XsumError::InfiniteInput {
+            sign: Sign::Negative,
+        }),
+    );
+    same_scaled_integer(&[INFINITY, -INFINITY], Err(XsumError::OppositeInfinities));
+
+    same_i128_scaled(&[], 0, Some(0));
+    same_i128_scaled(&[], -1_000, Some(0));
+    same_i128_scaled(&[1.5, -3.0], -1, Some(-3));
+    same_i128_scaled(&[1.5, -3.0], -3, Some(-12));
+    same_i128_scaled(&[1.5, -3.0], 0, None);
+    same_i128_scaled(&[96.0], 5, Some(3));
+    same_i128_scaled(&[96.0], 6, None);
+    same_i128_scaled(&[1.0], i64::MIN, None);
+    same_i128_scaled(&[1.0], i64::MAX, None);
+    same_i128_scaled(&[2.0_f64.powi(127)], 0, None);
+    same_i128_scaled(&[-(2.0_f64.powi(127))], 0, Some(i128::MIN));
+    same_i128_scaled(&[-(2.0_f64.powi(127)), -1.0], 0, None);
+    same_i128_scaled(&[2.0_f64.powi(127), -1.0], 0, Some(i128::MAX));
+    same_i128_scaled(&[2.0_f64.powi(200), -(2.0_f64.powi(200)), 7.0], 0, Some(7));
+    same_i128_scaled(&[INFINITY], 0, None);
+
+    // nothing below 2^-1074 or from 2^1069 on fits in the accumulator
+    assert!(XsumSmall::from_scaled_integer(Sign::Positive, &[3], -1075).is_none());
+    assert!(XsumSmall::from_scaled_integer(Sign::Positive, &[2], -1075).is_some());
+    assert!(XsumSmall::from_scaled_integer(Sign::Positive, &[1], 1069).is_none());
+    assert!(XsumSmall::from_scaled_integer(Sign::Positive, &[1], i64::MIN).is_none());
+    assert!(XsumSmall::from_scaled_integer(Sign::Positive, &[0, 0, 1], 940).is_some());
+    let mut xsumsmall = XsumSmall::from_scaled_integer(Sign::Negative, &[0, 0], 9_999)
+        .expect("XsumSmall::from_scaled_integer() of zero");
+    assert_eq!(
+        xsumsmall.sum().to_bits(),
+        (-0.0_f64).to_bits(),
+        "negative zero"
+    );
+}
+
+fn same_rounded(vec: &[f64], expected: [Result<i128, XsumError>; 5], expected_fract: f64) {
+    let modes = [
+        RoundingMode::HalfEven,
+        RoundingMode::HalfUp,
+        RoundingMode::TowardZero,
+        RoundingMode::Floor,
+        RoundingMode::Ceil,
+    ];
+
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(vec);
+    let actual = modes.map(|mode| xsumsmall.round_i128(mode));
+    assert_eq!(actual, expected, "xsumsmall.round_i128()");
+    let actual = [
+        xsumsmall.trunc_i128(),
+        xsumsmall.floor_i128(),
+        xsumsmall.ceil_i128(),
+    ];
+    assert_eq!(
+        actual,
+        [expected[2], expected[3], expected[4]],
+        "xsumsmall.trunc_i128(), floor_i128() and ceil_i128()"
+    );
+    let actual = xsumsmall.fract();
+    assert!(
+        same_bits(actual, expected_fract),
+        "xsumsmall.fract() must be {expected_fract:?}, got {actual:?}"
+    );
+
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_list(vec);
+    let actual = modes.map(|mode| xsumlarge.round_i128(mode));
+    assert_eq!(actual, expected, "xsumlarge.round_i128()");
+    let actual = [
+        xsumlarge.trunc_i128(),
+        xsumlarge.floor_i128(),
+        xsumlarge.ceil_i128(),
+    ];
+    assert_eq!(
+        actual,
+        [expected[2], expected[3], expected[4]],
+        "xsumlarge.trunc_i128(), floor_i128() and ceil_i128()"
+    );
+    let actual = xsumlarge.fract();
+    assert!(
+        same_bits(actual, expected_fract),
+        "xsumlarge.fract() must be {expected_fract:?}, got {actual:?}"
+    );
+
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_list(vec);
+    let actual = modes.map(|mode| xsumauto.round_i128(mode));
+    assert_eq!(actual, expected, "xsumauto.round_i128()");
+    let actual = [
+        xsumauto.trunc_i128(),
+        xsumauto.floor_i128(),
+        xsumauto.ceil_i128(),
+    ];
+    assert_eq!(
+        actual,
+        [expected[2], expected[3], expected[4]],
+        "xsumauto.trunc_i128(), floor_i128() and ceil_i128()"
+    );
+    let actual = xsumauto.fract();
+    assert!(
+        same_bits(actual, expected_fract),
+        "xsumauto.fract() must be {expected_fract:?}, got {actual:?}"
+    );
+}
+
+#[test]
+fn round_to_integer() {
+    // HalfEven, HalfUp, TowardZero, Floor, Ceil
+    same_rounded(&[], [Ok(0); 5], -0.0);
+    same_rounded(&[1.0, -1.0], [Ok(0); 5], 0.0);
+    same_rounded(&[-3.0], [Ok(-3); 5], -0.0);
+    same_rounded(&[2.5], [Ok(2), Ok(3), Ok(2), Ok(2), Ok(3)], 0.5);
+    same_rounded(&[3.5], [Ok(4), Ok(4), Ok(3), Ok(3), Ok(4)], 0.5);
+    same_rounded(&[-2.5], [Ok(-2), Ok(-3), Ok(-2), Ok(-3), Ok(-2)], -0.5);
+    same_rounded(&[-0.25], [Ok(0), Ok(0), Ok(0), Ok(-1), Ok(0)], -0.25);
+    same_rounded(&[0.75], [Ok(1), Ok(1), Ok(0), Ok(0), Ok(1)], 0.75);
+    same_rounded(&[5e-324], [Ok(0), Ok(0), Ok(0), Ok(0), Ok(1)], 5e-324);
+    // just above and below a tie
+    same_rounded(&[2.5, 5e-324], [Ok(3), Ok(3), Ok(2), Ok(2), Ok(3)], 0.5);
+    same_rounded(
+        &[-2.5, 5e-324],
+        [Ok(-2), Ok(-2), Ok(-2), Ok(-3), Ok(-2)],
+        -0.5,
+    );
+    // the fractional part is rounded once, to nearest
+    same_rounded(
+        &[1e20, 0.1],
+        [
+            Ok(100_000_000_000_000_000_000),
+            Ok(100_000_000_000_000_000_000),
+            Ok(100_000_000_000_000_000_000),
+            Ok(100_000_000_000_000_000_000),
+            Ok(100_000_000_000_000_000_001),
+        ],
+        0.1,
+    );
+    same_rounded(
+        &[1.0, -(2.0_f64.powi(-60))],
+        [Ok(1), Ok(1), Ok(0), Ok(0), Ok(1)],
+        1.0,
+    );
+
+    // the limits of i128
+    let min: f64 = -(2.0_f64.powi(127));
+    let overflow_pos = Err(XsumError::Overflow {
+        sign: Sign::Positive,
+    });
+    let overflow_neg = Err(XsumError::Overflow {
+        sign: Sign::Negative,
+    });
+    same_rounded(&[min], [Ok(i128::MIN); 5], -0.0);
+    same_rounded(
+        &[min, -0.5],
+        [
+            Ok(i128::MIN),
+            overflow_neg,
+            Ok(i128::MIN),
+            overflow_neg,
+            Ok(i128::MIN),
+        ],
+        -0.5,
+    );
+    same_rounded(&[-min], [overflow_pos; 5], 0.0);
+    same_rounded(
+        &[-min, -0.5],
+        [
+            overflow_pos,
+            overflow_pos,
+            Ok(i128::MAX),
+            Ok(i128::MAX),
+            overflow_pos,
+        ],
+        0.5,
+    );
+    same_rounded(&[f64::MAX, f64::MAX], [overflow_pos; 5], 0.0);
+    same_rounded(&[-f64::MAX, -1.5], [overflow_neg; 5], -0.5);
+
+    // special values
+    let nan = Err(XsumError::NaNInput { payload: 1 << 51 });
+    same_rounded(&[NaN, 1.0], [nan; 5], NaN);
+    let inf = Err(XsumError::InfiniteInput {
+        sign: Sign::Negative,
+    });
+    same_rounded(&[-INFINITY, 1.0], [inf; 5], NaN);
+    same_rounded(
+        &[INFINITY, -INFINITY],
+        [Err(XsumError::OppositeInfinities); 5],
+        NaN,
+    );
+}
+
+fn same_decimal(
+    vec: &[f64],
+    places: u32,
+    mode: RoundingMode,
+    expected: Result<(i128, u32), XsumError>,
+    expected_string: Result<&str, XsumError>,
+) {
+    let expected_string = expected_string.map(str::to_owned);
+
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(vec);
+    let actual = xsumsmall.to_decimal_rounded(places, mode);
+    assert_eq!(
+        actual, expected,
+        "xsumsmall.to_decimal_rounded({places}, {mode:?})"
+    );
+    let actual = xsumsmall.to_decimal_string(places, mode);
+    assert_eq!(
+        actual, expected_string,
+        "xsumsmall.to_decimal_string({places}, {mode:?})"
+    );
+
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_list(vec);
+    let actual = xsumlarge.to_decimal_rounded(places, mode);
+    assert_eq!(
+        actual, expected,
+        "xsumlarge.to_decimal_rounded({places}, {mode:?})"
+    );
+    let actual = xsumlarge.to_decimal_string(places, mode);
+    assert_eq!(
+        actual, expected_string,
+        "xsumlarge.to_decimal_string({places}, {mode:?})"
+    );
+
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_list(vec);
+    let actual = xsumauto.to_decimal_rounded(places, mode);
+    assert_eq!(
+        actual, expected,
+        "xsumauto.to_decimal_rounded({places}, {mode:?})"
+    );
+    let actual = xsumauto.to_decimal_string(places, mode);
+    assert_eq!(
+        actual, expected_string,
+        "xsumauto.to_decimal_string({places}, {mode:?})"
+    );
+}
+
+#[test]
+fn decimal_rounding() {
+    use RoundingMode::{Ceil, Floor, HalfEven, HalfUp, TowardZero};
+
+    same_decimal(&[], 2, HalfEven, Ok((0, 2)), Ok("0.00"));
+    same_decimal(&[0.125], 2, HalfEven, Ok((12, 2)), Ok("0.12"));
+    same_decimal(&[0.125], 2, HalfUp, Ok((13, 2)), Ok("0.13"));
+    same_decimal(&[-0.125], 2, HalfEven, Ok((-12, 2)), Ok("-0.12"));
+    same_decimal(&[-0.125], 2, HalfUp, Ok((-13, 2)), Ok("-0.13"));
+    same_decimal(&[-0.125], 2, TowardZero, Ok((-12, 2)), Ok("-0.12"));
+    same_decimal(&[-0.125], 2, Floor, Ok((-13, 2)), Ok("-0.13"));
+    same_decimal(&[-0.125], 2, Ceil, Ok((-12, 2)), Ok("-0.12"));
+    // 1.005 is just below the tie in binary, and 0.1 + 0.2 just above 0.3
+    same_decimal(&[1.005], 2, HalfUp, Ok((100, 2)), Ok("1.00"));
+    same_decimal(
+        &[0.1, 0.2],
+        17,
+        HalfEven,
+        Ok((30_000_000_000_000_002, 17)),
+        Ok("0.30000000000000002"),
+    );
+    same_decimal(
+        &[0.1, 0.2],
+        17,
+        TowardZero,
+        Ok((30_000_000_000_000_001, 17)),
+        Ok("0.30000000000000001"),
+    );
+    same_decimal(&[0.1, 0.2], 0, HalfEven, Ok((0, 0)), Ok("0"));
+    // a negative sum that rounds to zero has no sign
+    same_decimal(&[-0.001], 2, HalfEven, Ok((0, 2)), Ok("0.00"));
+    same_decimal(&[-0.001], 2, Floor, Ok((-1, 2)), Ok("-0.01"));
+    same_decimal(&[-1.5], 0, HalfEven, Ok((-2, 0)), Ok("-2"));
+    same_decimal(
+        &[5e-324],
+        323,
+        HalfEven,
+        Ok((0, 323)),
+        Ok(&format!("0.{}", "0".repeat(323))),
+    );
+    same_decimal(
+        &[-5e-324],
+        324,
+        HalfEven,
+        Ok((-5, 324)),
+        Ok(&format!("-0.{}5", "0".repeat(323))),
+    );
+
+    // the string has no length limit, unlike the i128 mantissa
+    let positive_overflow = Err(XsumError::Overflow {
+        sign: Sign::Positive,
+    });
+    same_decimal(
+        &[1e300],
+        1,
+        TowardZero,
+        positive_overflow,
+        Ok("1000000000000000052504760255204420248704468581108159154915854115511802457988908195786371375080447864043704443832883878176942523235360430575644792184786706982848387200926575803737830233794788090059368953234970799945081119038967640880074652742780142494579258788820056842838115669472196386865459400540160.0"),
+    );
+    same_decimal(
+        &[-1e30],
+        3,
+        HalfUp,
+        Ok((-1_000_000_000_000_000_019_884_624_838_656_000, 3)),
+        Ok("-1000000000000000019884624838656.000"),
+    );
+    same_decimal(
+        &[1e30, 1e30],
+        10,
+        HalfEven,
+        positive_overflow,
+        Ok("2000000000000000039769249677312.0000000000"),
+    );
+
+    // any sum is exact with 1075 places, and the places after are zeros
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(&[5e-324, 1.0]);
+    let exact = xsumsmall
+        .to_decimal_string(1_075, Floor)
+        .expect("to_decimal_string(1_075)");
+    assert!(
+        exact.starts_with("1.0000") && exact.ends_with("6250"),
+        "exact decimal of 1 + 2^-1074"
+    );
+    for mode in [HalfEven, HalfUp, TowardZero, Floor, Ceil] {
+        let actual = xsumsmall.to_decimal_string(1_200, mode);
+        assert_eq!(
+            actual,
+            Ok(format!("{exact}{}", "0".repeat(125))),
+            "to_decimal_string(1_200, {mode:?})"
+        );
+        let actual = xsumsmall.to_decimal_rounded(1_200, mode);
+        assert_eq!(
+            actual, positive_overflow,
+            "to_decimal_rounded(1_200, {mode:?})"
+        );
+    }
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(&[1.0, -1.0]);
+    assert_eq!(
+        xsumsmall.to_decimal_rounded(u32::MAX, Ceil),
+        Ok((0, u32::MAX)),
+        "zero with u32::MAX places"
+    );
+
+    // special values
+    let nan = Err(XsumError::NaNInput { payload: 1 << 51 });
+    same_decimal(&[NaN, 1.0], 2, HalfEven, nan, nan.map(|_| ""));
+    let inf = Err(XsumError::InfiniteInput {
+        sign: Sign::Positive,
+    });
+    same_decimal(&[INFINITY, 1.0], 2, HalfEven, inf, inf.map(|_| ""));
+}
+
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn same_order(a: &[f64], b: &[f64], expected: Ordering) {
+    let mut xsumsmall_a = XsumSmall::new();
+    xsumsmall_a.add_list(a);
+    let mut xsumsmall_b = XsumSmall::new();
+    xsumsmall_b.add_list(b);
+    assert_eq!(
+        xsumsmall_a.cmp(&xsumsmall_b),
+        expected,
+        "xsumsmall {a:?} cmp {b:?}"
+    );
+    assert_eq!(
+        xsumsmall_a == xsumsmall_b,
+        expected.is_eq(),
+        "xsumsmall {a:?} == {b:?}"
+    );
+    if expected.is_eq() {
+        assert_eq!(
+            hash_of(&xsumsmall_a),
+            hash_of(&xsumsmall_b),
+            "xsumsmall hash of {a:?} and {b:?}"
+        );
+    }
+
+    let mut xsumlarge_a = XsumLarge::new();
+    xsumlarge_a.add_list(a);
+    let mut xsumlarge_b = XsumLarge::new();
+    xsumlarge_b.add_list(b);
+    assert_eq!(
+        xsumlarge_a.cmp(&xsumlarge_b),
+        expected,
+        "xsumlarge {a:?} cmp {b:?}"
+    );
+    assert_eq!(
+        xsumlarge_a == xsumlarge_b,
+        expected.is_eq(),
+        "xsumlarge {a:?} == {b:?}"
+    );
+    if expected.is_eq() {
+        assert_eq!(
+            hash_of(&xsumlarge_a),
+            hash_of(&xsumlarge_b),
+            "xsumlarge hash of {a:?} and {b:?}"
+        );
+    }
+
+    let mut xsumauto_a = XsumAuto::new();
+    xsumauto_a.add_list(a);
+    let mut xsumauto_b = XsumAuto::new();
+    xsumauto_b.add_list(b);
+    assert_eq!(
+        xsumauto_a.cmp(&xsumauto_b),
+        expected,
+        "xsumauto {a:?} cmp {b:?}"
+    );
+    assert_eq!(
+        xsumauto_a == xsumauto_b,
+        expected.is_eq(),
+        "xsumauto {a:?} == {b:?}"
+    );
+    if expected.is_eq() {
+        assert_eq!(
+            hash_of(&xsumauto_a),
+            hash_of(&xsumauto_b),
+            "xsumauto hash of {a:?} and {b:?}"
+        );
+    }
+}
+
+fn same_sign(vec: &[f64], x: f64, expected: Option<Ordering>, expected_signum: f64) {
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(vec);
+    assert_eq!(xsumsmall.cmp_f64(x), expected, "xsumsmall.cmp_f64({x})");
+    assert!(
+        same_bits(xsumsmall.signum(), expected_signum),
+        "xsumsmall.signum() must be {expected_signum}"
+    );
+    assert_eq!(
+        xsumsmall.is_zero(),
+        expected_signum == 0.0,
+        "xsumsmall.is_zero()"
+    );
+
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_list(vec);
+    assert_eq!(xsumlarge.cmp_f64(x), expected, "xsumlarge.cmp_f64({x})");
+    assert!(
+        same_bits(xsumlarge.signum(), expected_signum),
+        "xsumlarge.signum() must be {expected_signum}"
+    );
+    assert_eq!(
+        xsumlarge.is_zero(),
+        expected_signum == 0.0,
+        "xsumlarge.is_zero()"
+    );
+
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_list(vec);
+    assert_eq!(xsumauto.cmp_f64(x), expected, "xsumauto.cmp_f64({x})");
+    assert!(
+        same_bits(xsumauto.signum(), expected_signum),
+        "xsumauto.signum() must be {expected_signum}"
+    );
+    assert_eq!(
+        xsumauto.is_zero(),
+        expected_signum == 0.0,
+        "xsumauto.is_zero()"
+    );
+}
+
+#[test]
+fn exact_order() {
+    let tiny: f64 = 2.0_f64.powi(-60);
+
+    // the same exact value, whatever the order and rounding of the inputs
+    same_order(&[0.1, 0.2, 0.3], &[0.3, 0.2, 0.1], Ordering::Equal);
+    same_order(&[], &[1.0, -1.0], Ordering::Equal);
+    same_order(&[-0.0], &[0.0], Ordering::Equal);
+    same_order(&[1e300, 1.0, -1e300], &[0.5, 0.5], Ordering::Equal);
+    same_order(&[0.1, 0.2, 0.3], &[0.6], Ordering::Greater);
+    same_order(&[1.0, tiny], &[1.0], Ordering::Greater);
+    same_order(&[-1.0, -tiny], &[-1.0], Ordering::Less);
+    same_order(&[-tiny], &[0.0], Ordering::Less);
+    same_order(&[5e-324], &[-f64::MAX], Ordering::Greater);
+    same_order(&[f64::MAX, f64::MAX], &[f64::MAX], Ordering::Greater);
+    same_order(&[1.0; 1_500], &[1_500.0], Ordering::Equal);
+    same_order(&[0.1; 1_500], &[0.1; 1_499], Ordering::Greater);
+
+    // -Inf < finite < +Inf < NaN, and all NaNs are equal
+    same_order(&[-INFINITY], &[-f64::MAX, -f64::MAX], Ordering::Less);
+    same_order(&[INFINITY], &[f64::MAX, f64::MAX], Ordering::Greater);
+    same_order(&[INFINITY, 1.0], &[INFINITY], Ordering::Equal);
+    same_order(&[NaN], &[INFINITY], Ordering::Greater);
+    same_order(&[INFINITY, -INFINITY], &[NaN, 1.0], Ordering::Equal);
+
+    // min, max and clamp
+    let small = |vec: &[f64]| {
+        let mut xsumsmall = XsumSmall::new();
+        xsumsmall.add_list(vec);
+        xsumsmall
+    };
+    assert_eq!(
+        small(&[1.0, tiny]).max(small(&[1.0])).sum(),
+        1.0,
+        "max keeps the exact value"
+    );
+    assert!(
+        small(&[1.0, tiny]).max(small(&[1.0])) == small(&[tiny, 1.0]),
+        "max is the larger sum"
+    );
+    assert!(
+        small(&[1.0, tiny]).min(small(&[1.0])) == small(&[1.0]),
+        "min is the smaller sum"
+    );
+    assert!(
+        small(&[3.0]).clamp(small(&[1.0]), small(&[2.0])) == small(&[2.0]),
+        "clamp to max"
+    );
+
+    // large chunks already transferred by signum() are not added again
+    let vec: Vec<f64> = (0..2_000).map(|i| (f64::from(i) - 999.0) / 7.0).collect();
+    let mut xsumlarge_a = XsumLarge::new();
+    xsumlarge_a.add_list(&vec[..1_000]);
+    assert_eq!(xsumlarge_a.signum(), -1.0, "xsumlarge.signum() of half");
+    xsumlarge_a.add_list(&vec[1_000..]);
+    assert_eq!(xsumlarge_a.signum(), 1.0, "xsumlarge.signum() of all");
+    assert!(!xsumlarge_a.is_zero(), "xsumlarge.is_zero() of all");
+    let mut xsumlarge_b = XsumLarge::new();
+    xsumlarge_b.add_list(&vec);
+    assert!(
+        xsumlarge_a == xsumlarge_b,
+        "xsumlarge after signum() == fresh"
+    );
+    assert_eq!(
+        hash_of(&xsumlarge_a),
+        hash_of(&xsumlarge_b),
+        "xsumlarge hash after signum()"
+    );
+    assert_eq!(
+        xsumlarge_a.sum(),
+        xsumlarge_b.sum(),
+        "xsumlarge.sum() after signum()"
+    );
+}
+
+#[test]
+fn exact_sign() {
+    let tiny: f64 = 2.0_f64.powi(-60);
+
+    same_sign(&[], 0.0, Some(Ordering::Equal), 0.0);
+    same_sign(&[1.0, -1.0], -0.0, Some(Ordering::Equal), 0.0);
+    same_sign(&[1.0, tiny], 1.0, Some(Ordering::Greater), 1.0);
+    same_sign(&[-1.0, -tiny], -1.0, Some(Ordering::Less), -1.0);
+    same_sign(&[1e-300, 1e300, -1e300], 1e-300, Some(Ordering::Equal), 1.0);
+    same_sign(&[-5e-324], 0.0, Some(Ordering::Less), -1.0);
+    same_sign(&[5e-324, 5e-324], 1e-323, Some(Ordering::Equal), 1.0);
+    same_sign(
+        &[f64::MAX, f64::MAX],
+        f64::MAX,
+        Some(Ordering::Greater),
+        1.0,
+    );
+    same_sign(&[f64::MAX, f64::MAX], INFINITY, Some(Ordering::Less), 1.0);
+    same_sign(&[-1.0; 2_000], -2_000.0, Some(Ordering::Equal), -1.0);
+
+    // special values
+    same_sign(&[1.0], NaN, None, 1.0);
+    same_sign(&[NaN], 1.0, None, NaN);
+    same_sign(&[-INFINITY, 1.0], -INFINITY, Some(Ordering::Equal), -1.0);
+    same_sign(&[INFINITY], f64::MAX, Some(Ordering::Greater), 1.0);
+    same_sign(&[INFINITY, -INFINITY], 0.0, None, NaN);
+}
+
+fn same_exact_f64(vec: &[f64], expected: f64) {
+    let whole: ExactF64 = vec.iter().sum();
+    assert!(
+        same_bits(whole.to_f64(), expected),
+        "sum of {vec:?} must be {expected}"
+    );
+
+    // the same value from parts, in any grouping
+    for split in 0..=vec.len() {
+        let (head, tail) = vec.split_at(split);
+        let parts: ExactF64 = [head.iter().sum::<ExactF64>(), tail.iter().sum()]
+            .into_iter()
+            .sum();
+        assert!(
+            same_bits(parts.to_f64(), expected),
+            "sum of {head:?} and {tail:?} must be {expected}"
+        );
+        if expected.is_finite() {
+            assert!(parts == whole, "sum of {head:?} and {tail:?}");
+        }
+
+        let difference: ExactF64 = head.iter().sum::<ExactF64>() - -tail.iter().sum::<ExactF64>();
+        assert!(
+            same_bits(difference.to_f64(), expected),
+            "difference of {head:?} and -{tail:?} must be {expected}"
+        );
+    }
+}
+
+#[test]
+fn exact_f64() {
+    let tiny: f64 = 2.0_f64.powi(-60);
+
+    same_exact_f64(&[], -0.0);
+    same_exact_f64(&[0.0, -0.0], 0.0);
+    same_exact_f64(&[-0.0, -0.0], -0.0);
+    same_exact_f64(&[0.1, 0.2, -0.3], 2.7755575615628914e-17);
+    same_exact_f64(&[1.0, tiny, -1.0], tiny);
+    same_exact_f64(&[1e300, 1.0, -1e300, 5e-324], 1.0);
+    same_exact_f64(&[f64::MAX, f64::MAX, -f64::MAX], f64::MAX);
+    same_exact_f64(&[f64::MAX, f64::MAX], INFINITY);
+    same_exact_f64(&[1.0, -INFINITY], -INFINITY);
+    same_exact_f64(&[INFINITY, 1.0, -INFINITY], NaN);
+    same_exact_f64(&[NaN, 1.0], NaN);
+
+    // negation and scaling
+    let value = ExactF64::from(1.0) + tiny;
+    assert!(same_bits((-value.clone()).to_f64(), -1.0), "negated value");
+    assert!(
+        -(-value.clone()) == value,
+        "negation twice is the same value"
+    );
+    assert!(
+        same_bits((-ExactF64::from(0.0)).to_f64(), -0.0),
+        "negated +0"
+    );
+    assert!(same_bits((-ExactF64::zero()).to_f64(), 0.0), "negated -0");
+    assert!(
+        same_bits((-ExactF64::from(INFINITY)).to_f64(), -INFINITY),
+        "negated +Inf"
+    );
+    assert!(
+        value.clone().mul_pow2(60) - 2.0_f64.powi(60) == ExactF64::from(1.0),
+        "scaled value"
+    );
+    assert!(
+        value.clone().mul_pow2(1_000).mul_pow2(-1_000) == value,
+        "scaling beyond f64::MAX is exact"
+    );
+    assert!(
+        same_bits(ExactF64::from(5e-324).mul_pow2(-1).to_f64(), 0.0),
+        "scaling below the smallest subnormal rounds"
+    );
+    assert!(
+        (value.clone() + 5e-324).mul_pow2(-1).mul_pow2(1) == value,
+        "scaling below the smallest subnormal keeps the bits above it"
+    );
+
+    // assignment operators
+    let mut total = ExactF64::zero();
+    total += 0.1;
+    total += &ExactF64::from(0.2);
+    total -= 0.3;
+    total -= ExactF64::from(tiny);
+    total -= &ExactF64::from(-tiny);
+    assert!(
+        total == [0.1, 0.2, -0.3].iter().sum(),
+        "total after assignments"
+    );
+}
+
+fn same_error(vec: &[f64], candidate: f64, expected: f64, expected_ulps: u64) {
+    let mut xsumsmall = XsumSmall::new();
+    xsumsmall.add_list(vec);
+    assert!(
+        same_bits(xsumsmall.error_of(candidate), expected),
+        "xsumsmall.error_of({candidate}) must be {expected}"
+    );
+    assert_eq!(
+        xsumsmall.ulps_from(candidate),
+        expected_ulps,
+        "xsumsmall.ulps_from({candidate})"
+    );
+
+    let mut xsumlarge = XsumLarge::new();
+    xsumlarge.add_list(vec);
+    assert!(
+        same_bits(xsumlarge.error_of(candidate), expected),
+        "xsumlarge.error_of({candidate}) must be {expected}"
+    );
+    assert_eq!(
+        xsumlarge.ulps_from(candidate),
+        expected_ulps,
+        "xsumlarge.ulps_from({candidate})"
+    );
+
+    let mut xsumauto = XsumAuto::new();
+    xsumauto.add_list(vec);
+    assert!(
+        same_bits(xsumauto.error_of(candidate), expected),
+        "xsumauto.error_of({candidate}) must be {expected}"
+    );
+    assert_eq!(
+        xsumauto.ulps_from(candidate),
+        expected_ulps,
+        "xsumauto.ulps_from({candidate})"
+    );
+}
+
+#[test]
+fn error_of_candidate() {
+    let tiny: f64 = 2.0_f64.powi(-60);
+
+    same_error(&[], 0.0, 0.0, 0);
+    same_error(&[-0.0], 0.0, 0.0, 0);
+    same_error(&[1.0, tiny], 1.0, tiny, 0);
+    same_error(&[1.0, tiny], 0.9999999999999999, 1.1188966420050406e-16, 1);
+    same_error(
+        &[0.1, 0.2, 0.3],
+        0.6000000000000001,
+        -8.326672684688674e-17,
+        0,
+    );
+    same_error(&[5e-324], 0.0, 5e-324, 1);
+    same_error(&[-1.0], 1.0, -2.0, 1 << 53);
+    same_error(&[1e300, -1e300], 1e300, -1e300, 6_724_873_095_247_260);
+    same_error(&[f64::MAX, f64::MAX], f64::MAX, f64::MAX, (1 << 53) - 1);
+    same_error(
+        &[f64::MAX, f64::MAX],
+        -f64::MAX,
+        INFINITY,
+        3 * (1 << 53) - 3,
+    );
+    same_error(&[1e300], 5e-324, 1e300, u64::MAX);
+    same_error(&[0.1; 2_000], 200.0, 1.1102230246251565e-14, 0);
+    same_error(&[0.1; 2_000], 199.9, 0.10000000000000542, 3_518_437_208_883);
+
+    // special values
+    same_error(&[1.0], INFINITY, -INFINITY, u64::MAX);
+    same_error(&[1.0], NaN, NaN, u64::MAX);
+    same_error(&[INFINITY, 1.0], INFINITY, NaN, 0);
+    same_error(&[-INFINITY], 1.0, -INFINITY, u64::MAX);
+    same_error(&[NaN], 1.0, NaN, u64::MAX);
+}
+
+fn same_profile(vec: &[f64], expected: (f64, f64, f64), expected_counts: (u64, u64, u64)) {
+    let (expected_sum, expected_abs_sum, expected_condition) = expected;
+    let mut xsumprofile = XsumProfile::new();
+    xsumprofile.add_list(vec);
+    assert!(
+        same_bits(xsumprofile.sum(), expected_sum),
+        "xsumprofile.sum() must be {expected_sum}"
+    );
+    assert!(
+        same_bits(xsumprofile.abs_sum(), expected_abs_sum),
+        "xsumprofile.abs_sum() must be {expected_abs_sum}"
+    );
+    assert!(
+        same_bits(xsumprofile.condition_number(), expected_condition),
+        "xsumprofile.condition_number() must be {expected_condition}"
+    );
+    assert_eq!(
+        (
+            xsumprofile.negative_count(),
+            xsumprofile.zero_count(),
+            xsumprofile.subnormal_count()
+        ),
+        expected_counts,
+        "xsumprofile negative, zero and subnormal counts"
+    );
+    assert_eq!(xsumprofile.len(), vec.len(), "xsumprofile.len()");
+
+    // adding one value at a time gives the same profile
+    let mut xsumprofile_one = XsumProfile::new();
+    for &value in vec {
+        xsumprofile_one.add(value);
+    }
+    assert_eq!(
+        xsumprofile_one.report(),
+        xsumprofile.report(),
+        "xsumprofile.report() after add()"
+    );
+}
+
+#[test]
+fn profile() {
+    same_profile(&[], (-0.0, -0.0, NaN), (0, 0, 0));
+    same_profile(&[0.0, -0.0], (0.0, 0.0, NaN), (0, 2, 0));
+    same_profile(&[1.0, 2.0, 3.0], (6.0, 6.0, 1.0), (0, 0, 0));
+    same_profile(&[1.0, -1.0], (0.0, 2.0, INFINITY), (1, 0, 0));
+    same_profile(&[1e-310, -1e-310, 1.0], (1.0, 1.0, 1.0), (1, 0, 2));
+    same_profile(
+        &[f64::MAX, f64::MAX, -f64::MAX],
+        (f64::MAX, INFINITY, 3.0),
+        (1, 0, 0),
+    );
+    let mut vec = vec![0.1; 1_500];
+    vec.push(-150.0);
+    same_profile(
+        &vec,
+        (8.326672684688674e-15, 300.0, 3.602879701896397e16),
+        (1, 0, 0),
+    );
+    same_profile(&[1.0, NaN], (NaN, NaN, NaN), (0, 0, 0));
+    same_profile(&[1.0, -INFINITY], (-INFINITY, INFINITY, NaN), (1, 0, 0));
+
+    // the exponent histogram
+    let mut xsumprofile = XsumProfile::new();
+    xsumprofile.add_list(&vec);
+    xsumprofile.add_list(&[5e-324, -1e-310, f64::MAX, INFINITY, NaN, 0.0]);
+    assert_eq!(
+        xsumprofile.exponent_histogram(),
+        vec![(-1074, 1), (-1030, 1), (-4, 1_500), (7, 1), (1023, 1)],
+        "xsumprofile.exponent_histogram()"
+    );
+    assert_eq!(
+        (xsumprofile.min_exponent(), xsumprofile.max_exponent()),
+        (Some(-1074), Some(1023)),
+        "xsumprofile exponent range"
+    );
+    assert!(
+        xsumprofile
+            .report()
+            .ends_with("  2^-4: 1500\n  2^7: 1\n  2^1023: 1\n"),
+        "xsumprofile.report()"
+    );
+    xsumprofile.clear();
+    assert_eq!(
+        xsumprofile.exponent_histogram(),
+        vec![],
+        "xsumprofile.exponent_histogram() after clear()"
+    );
+}
+
+fn same_summation<S: xsum::summation::Summation>(vec: &[f64], expected: f64) {
+    let name = S::name();
+    let mut summation = S::new();
+    summation.add_list(vec);
+    assert!(
+        same_bits(summation.sum(), expected),
+        "{name} add_list() sum must be {expected}"
+    );
+
+    summation.clear();
+    assert!(
+        same_bits(summation.sum(), -0.0),
+        "{name} sum must be -0.0 after clear()"
+    );
+    for &value in vec {
+        summation.add(value);
+    }
+    assert!(
+        same_bits(summation.sum(), expected),
+        "{name} add() sum must be {expected}"
+    );
+}
+
+#[test]
+fn summation_strategies() {
+    // exact for all of them
+    for vec in [&[][..], &[-0.0], &[0.0, -0.0], &[1.0, 2.0, 3.0]] {
+        let expected = vec.xsum();
+        same_summation::<NaiveSum>(vec, expected);
+        same_summation::<PairwiseSum>(vec, expected);
+        same_summation::<KahanSum>(vec, expected);
+        same_summation::<NeumaierSum>(vec, expected);
+        same_summation::<XsumSmall>(vec, expected);
+        same_summation::<XsumLarge>(vec, expected);
+        same_summation::<XsumAuto>(vec, expected);
+    }
+    let vec: Vec<f64> = (0..1_000).map(f64::from).collect();
+    same_summation::<NaiveSum>(&vec, 499_500.0);
+    same_summation::<PairwiseSum>(&vec, 499_500.0);
+    same_summation::<KahanSum>(&vec, 499_500.0);
+    same_summation::<NeumaierSum>(&vec, 499_500.0);
+
+    // rounding errors of many additions
+    let vec = vec![0.1; 10_000];
+    same_summation::<NaiveSum>(&vec, 1000.0000000001588);
+    same_summation::<PairwiseSum>(&vec, 999.9999999999977);
+    same_summation::<KahanSum>(&vec, 1000.0);
+    same_summation::<NeumaierSum>(&vec, 1000.0);
+    same_summation::<XsumAuto>(&vec, 1000.0);
+
+    // cancellation between large values
+    let vec = [1.0, 1e100, 1.0, -1e100];
+    same_summation::<NaiveSum>(&vec, 0.0);
+    same_summation::<PairwiseSum>(&vec, 0.0);
+    same_summation::<KahanSum>(&vec, 0.0);
+    same_summation::<NeumaierSum>(&vec, 2.0);
+    same_summation::<XsumSmall>(&vec, 2.0);
+    same_summation::<XsumLarge>(&vec, 2.0);
+    same_summation::<XsumAuto>(&vec, 2.0);
+
+    // special values
+    same_summation::<NaiveSum>(&[1.0, INFINITY], INFINITY);
+    same_summation::<PairwiseSum>(&[1.0, INFINITY], INFINITY);
+    same_summation::<KahanSum>(&[1.0, INFINITY], INFINITY);
+    same_summation::<KahanSum>(&[INFINITY, 1.0], NaN);
+    same_summation::<NeumaierSum>(&[1.0, INFINITY], INFINITY);
+    same_summation::<NeumaierSum>(&[1.0, NaN], NaN);
+    same_summation::<XsumAuto>(&[1.0, INFINITY], INFINITY);
+}
+
+#[test]
+fn reference_oracle() {
+    assert!(
+        same_bits(reference_sum(&[]), -0.0),
+        "reference sum of no values must be -0.0"
+    );
+    assert!(
+        reference_sum(&[1.0, NaN]).is_nan(),
+        "reference sum with NaN must be NaN"
+    );
+    assert_eq!(
+        reference_sum(&[1.0, 2.0_f64.powi(-53)]),
+        1.0,
+        "reference ties round to even"
+    );
+    assert_eq!(
+        reference_sum(&[1.0, 2.0_f64.powi(-53), 5e-324]),
+        1.0000000000000002,
+        "reference rounds up above a tie"
+    );
+    assert_eq!(
+        reference_sum(&[-f64::MAX, -f64::MAX]),
+        -INFINITY,
+        "reference overflows to -inf"
+    );
+
+    assert_agrees(&[]);
+    assert_agrees(&[-0.0]);
+    assert_agrees(&[0.0, -0.0]);
+    assert_agrees(&[5e-324, -5e-324]);
+    assert_agrees(&[f64::MIN_POSITIVE, -5e-324]);
+    assert_agrees(&[f64::MAX, f64::MAX, -f64::MAX]);
+    assert_agrees(&[f64::MAX, 9.979_201_547_673_598e291]);
+    assert_agrees(&[1.0, INFINITY, -INFINITY]);
+    assert_agrees(&[1e308, 1e308, 0.1, 0.1, 1e30, 0.1, -1e30, -1e308, -1e308]);
+
+    // values with random bits, from a fixed xorshift sequence
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    for len in [2, 10, 100, 1_000, 5_000] {
+        let vec: Vec<f64> = (0..len)
+            .map(|_| f64::from_bits(next()))
+            .filter(|value| value.is_finite())
+            .collect();
+        assert_agrees(&vec);
+
+        // near cancellation, with small values left over
+        let mut vec: Vec<f64> = vec.iter().flat_map(|&value| [value, -value]).collect();
+        vec.extend((0..len).map(|_| f64::from_bits(next() >> 12)));
+        assert_agrees(&vec);
+    }
+}
+
+#[test]
+fn testgen_data() {
+    for seed in 0..10 {
+        let mut testgen = TestGen::new(seed);
+        for len in [6, 100, 2_000] {
+            for condition in [1e10, 1e30, 1e100, 1e300] {
+                let data = testgen.ill_conditioned(len, condition);
+                assert_eq!(data.values().len(), len, "ill_conditioned() length");
+                let ratio = data.condition_number() / condition;
+                assert!(
+                    (1e-2..1e4).contains(&ratio),
+                    "ill_conditioned() condition number must be about {condition:e}"
+                );
+                same_value(data.values(), data.expected());
+            }
+
+            let data = testgen.exponent_spread(len);
+            assert_eq!(data.values().len(), len, "exponent_spread() length");
+            same_value(data.values(), data.expected());
+
+            let data = testgen.cancellation(len);
+            assert_eq!(data.values().len(), len, "cancellation() length");
+            same_value(data.values(), data.expected());
+
+            let data = testgen.subnormals(len);
+            assert_eq!(data.values().len(), len, "subnormals() length");
+            assert!(
+                data.values()
+                    .iter()
+                    .all(|value| value.abs() < 4.0 * f64::MIN_POSITIVE),
+                "subnormals() values must be tiny"
+            );
+            same_value(data.values(), data.expected());
+
+            let data = testgen.special_values(len);
+            assert_eq!(data.values().len(), len, "special_values() length");
+            assert!(
+                !data.expected().is_finite(),
+                "special_values() sum must not be finite"
+            );
+            same_value(data.values(), data.expected());
+        }
+    }
+
+    let a = TestGen::new(11).cancellation(500).into_values();
+    let b = TestGen::new(11).cancellation(500).into_values();
+    let c = TestGen::new(12).cancellation(500).into_values();
+    assert_eq!(a, b, "the same seed must give the same values");
+    assert_ne!(a, c, "another seed must give other values");
+}
+
+#[test]
+fn concurrent_xsum() {
+    let total = ConcurrentXsum::new();
+    assert!(
+        same_bits(total.sum(), -0.0),
+        "concurrent sum of no values must be -0.0"
+    );
+    total.add_list(&[-0.0, -0.0]);
+    assert!(
+        same_bits(total.sum(), -0.0),
+        "concurrent sum of -0.0 values must be -0.0"
+    );
+    total.add(0.0);
+    assert!(
+        same_bits(total.sum(), 0.0),
+        "concurrent sum with 0.0 must be 0.0"
+    );
+    total.add(INFINITY);
+    assert_eq!(total.sum(), INFINITY, "concurrent sum with inf");
+    total.add(-INFINITY);
+    assert!(total.sum().is_nan(), "concurrent sum with inf and -inf");
+
+    // many threads adding hard data at once
+    let mut testgen = TestGen::new(50);
+    let parts: Vec<Vec<f64>> = (0..8)
+        .map(|i| match i % 4 {
+            0 => testgen.ill_conditioned(5_000, 1e100).into_values(),
+            1 => testgen.exponent_spread(5_000).into_values(),
+            2 => testgen.cancellation(5_000).into_values(),
+            _ => testgen.subnormals(5_000).into_values(),
+        })
+        .collect();
+    let all: Vec<f64> = parts.concat();
+    let total = ConcurrentXsum::new();
+    std::thread::scope(|scope| {
+        for (i, part) in parts.iter().enumerate() {
+            let total = &total;
+            scope.spawn(move || {
+                if i % 2 == 0 {
+                    total.add_list(part);
+                } else {
+                    for &value in part {
+                        total.add(value);
+                    }
+                }
+            });
+        }
+    });
+    assert_eq!(total.len(), all.len(), "concurrent len");
+    assert!(
+        same_bits(total.sum(), reference_sum(&all)),
+        "concurrent sum must be the exact sum"
+    );
+
+    // carries between chunks while more threads add than there are cores
+    let total = ConcurrentXsum::new();
+    std::thread::scope(|scope| {
+        for _ in 0..16 {
+            scope.spawn(|| {
+                for _ in 0..25_000 {
+                    total.add(f64::MAX / 4.0);
+                    total.add(1.0);
+                    total.add(-f64::MAX / 4.0);
+                }
+            });
+        }
+    });
+    assert_eq!(total.sum(), 400_000.0, "concurrent sum with carries");
+
+    // add_list() is seen all at once
+    let total = ConcurrentXsum::new();
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..10_000 {
+                    total.add_list(&[1e300, 1.0, -1e300]);
+                }
+            });
+        }
+        for _ in 0..1_000 {
+            let sum = total.sum();
+            assert!(
+                sum.fract() == 0.0 && (0.0..=40_000.0).contains(&sum),
+                "concurrent sum must be a count of whole lists, got {sum}"
+            );
+        }
+    });
+    assert_eq!(total.sum(), 40_000.0, "concurrent sum of lists");
+
+    // snapshots while the writers never pause
+    let total = ConcurrentXsum::new();
+    let stop = std::sync::atomic::AtomicBool::new(false);
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                while !stop.load(std::sync::atomic::Ordering::Relaxed) {
+                    total.add_list(&[0.1; 64]);
+                }
+            });
+        }
+        for _ in 0..100 {
+            let mut snapshot = total.snapshot();
+            let len = snapshot.len();
+            let mut expected = XsumSmall::new();
+            expected.add_repeated(0.1, len as u64);
+            assert!(
+                len % 64 == 0 && snapshot.sum() == expected.sum(),
+                "concurrent snapshot must hold whole lists, got {len} values"
+            );
+        }
+        stop.store(true, std::sync::atomic::Ordering::Relaxed);
+    });
+
+    let mut total = ConcurrentXsum::new();
+    total.add(1.0);
+    Xsum::clear(&mut total);
+    assert!(total.is_empty(), "concurrent clear()");
+}